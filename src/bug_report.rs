@@ -0,0 +1,110 @@
+// Links to the program's issue form, prefilled with the error, for errors that
+// end the program.
+
+use crate::config;
+
+// Placeholders in the link that are replaced by the summary and the report
+const TITLE_PLACEHOLDER: &str = "{title}";
+const BODY_PLACEHOLDER: &str = "{body}";
+
+/// Makes print_and_exit() end the error with a link to report it, e.g. to
+/// the program's issue form. In the template, `{title}` is replaced by the
+/// summary of the error, and `{body}` by the error without any styling, cut
+/// short to keep the link a reasonable length (see
+/// [`Config::bug_report_max_bytes`](crate::Config::bug_report_max_bytes)).
+/// Both are percent-encoded.
+/// # Example
+/// ```
+/// user_error::bug_report_url("https://github.com/me/mytool/issues/new?title={title}&body={body}");
+/// ```
+pub fn bug_report_url<S: Into<String>>(template: S) {
+    let template = template.into();
+    config::update(|config| config.bug_report_url = Some(template));
+}
+
+/// Fills in the template with the title and the body, percent-encoded, the
+/// body cut short to at most `max_body_bytes` bytes first.
+pub(crate) fn fill(template: &str, title: &str, body: &str, max_body_bytes: usize) -> String {
+    template
+        .replace(TITLE_PLACEHOLDER, &percent_encode(title))
+        .replace(
+            BODY_PLACEHOLDER,
+            &percent_encode(truncate(body, max_body_bytes)),
+        )
+}
+
+// Encodes every byte of the UTF-8 text as %XX, except the characters that
+// never need it (RFC 3986's unreserved characters)
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(char::from(byte))
+            }
+            byte => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+// Cuts the text short to at most `max` bytes, without splitting a character
+fn truncate(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_encode_test() {
+        assert_eq!(percent_encode("Az09-._~"), "Az09-._~");
+        assert_eq!(
+            percent_encode("File not found\n - main.db"),
+            "File%20not%20found%0A%20-%20main.db"
+        );
+        assert_eq!(
+            percent_encode("a&b=c?d#e/f+g%"),
+            "a%26b%3Dc%3Fd%23e%2Ff%2Bg%25"
+        );
+        assert_eq!(
+            percent_encode("caf\u{e9} \u{1f4a1}"),
+            "caf%C3%A9%20%F0%9F%92%A1"
+        );
+    }
+
+    #[test]
+    fn truncate_test() {
+        assert_eq!(truncate("main.db", 100), "main.db");
+        assert_eq!(truncate("main.db", 4), "main");
+        /* 'é' takes two bytes, and isn't split */
+        assert_eq!(truncate("caf\u{e9}", 4), "caf");
+        assert_eq!(truncate("caf\u{e9}", 5), "caf\u{e9}");
+    }
+
+    #[test]
+    fn fill_test() {
+        let template = "https://example.com/new?title={title}&body={body}";
+        assert_eq!(
+            fill(
+                template,
+                "Failed to sync",
+                "Error: Failed to sync\n - Timed out",
+                21
+            ),
+            "https://example.com/new?title=Failed%20to%20sync&body=Error%3A%20Failed%20to%20sync"
+        );
+        assert_eq!(
+            fill("https://example.com/new", "a b", "c", 10),
+            "https://example.com/new"
+        );
+    }
+}
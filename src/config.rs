@@ -0,0 +1,233 @@
+// Every global option that affects how errors are rendered and printed, kept
+// together so that they can be set (and inspected) all at once.
+
+use crate::{OutputStyle, Stream, Theme};
+use std::borrow::Cow;
+use std::sync::RwLock;
+
+/// The options used to render and print all errors. Anything left unset is
+/// detected from the environment. Build one up and install it with
+/// [`configure()`]. Each option can also be changed on its own with the
+/// matching setter, e.g. [`set_width()`](crate::set_width).
+/// # Example
+/// ```
+/// use user_error::{configure, Config, OutputStyle};
+/// configure(Config::new().width(80).style(OutputStyle::Plain).quiet(true));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Theme used to pretty print errors, or None to pick one that suits the
+    /// terminal background
+    pub theme: Option<Theme>,
+    /// Whether rendering uses only 7-bit ASCII, or None to detect it from
+    /// TERM and the locale
+    pub ascii_only: Option<bool>,
+    /// Width of the terminal in columns, or None to use COLUMNS
+    pub width: Option<usize>,
+    /// Maximum width errors are wrapped to, or None for no maximum
+    pub max_width: Option<usize>,
+    /// Stream errors are printed to, unless a different one is chosen for a
+    /// particular error
+    pub stream: Stream,
+    /// How errors are rendered when they are printed, or None to use
+    /// USER_ERROR_STYLE
+    pub style: Option<OutputStyle>,
+    /// Whether only the summary of an error is printed, leaving out the
+    /// reasons and help text
+    pub quiet: bool,
+    /// Maximum number of reasons printed, or None to print all of them
+    pub max_reasons: Option<usize>,
+    /// Whether extra detail meant for bug reports is printed, e.g. the
+    /// environment snapshot of errors that include one
+    pub verbose: bool,
+    /// Name of the program, used to tell the user how to see what was left
+    /// out, or None to use the name it was run with
+    pub app_name: Option<String>,
+    /// Command line flag that makes the program print everything
+    pub verbose_flag: Cow<'static, str>,
+    /// Version of the program, printed in environment snapshots
+    pub app_version: Option<String>,
+    /// Number of lines above which printed errors are shown through the
+    /// pager, or None to never page them
+    pub page_threshold: Option<usize>,
+    /// Link offered for reporting errors that end the program, with `{title}`
+    /// and `{body}` placeholders, or None to offer none
+    pub bug_report_url: Option<String>,
+    /// Maximum number of bytes of the error put in the bug report link,
+    /// before it is encoded
+    pub bug_report_max_bytes: usize,
+}
+
+impl Config {
+    /// A configuration with every option left unset, so that all of them are
+    /// detected from the environment.
+    pub const fn new() -> Config {
+        Config {
+            theme: None,
+            ascii_only: None,
+            width: None,
+            max_width: None,
+            stream: Stream::Stderr,
+            style: None,
+            quiet: false,
+            max_reasons: None,
+            verbose: false,
+            app_name: None,
+            verbose_flag: Cow::Borrowed("--verbose"),
+            app_version: None,
+            page_threshold: None,
+            bug_report_url: None,
+            bug_report_max_bytes: 2000,
+        }
+    }
+
+    /// Returns the configuration currently installed.
+    pub fn current() -> Config {
+        read(Config::clone)
+    }
+
+    /// Sets the theme used to pretty print errors.
+    pub fn theme(mut self, theme: Theme) -> Config {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Forces rendering to use only 7-bit ASCII (true), or allows Unicode
+    /// and ANSI escape codes (false).
+    pub fn ascii_only(mut self, ascii_only: bool) -> Config {
+        self.ascii_only = Some(ascii_only);
+        self
+    }
+
+    /// Sets the width of the terminal in columns. 0 means COLUMNS is used.
+    pub fn width(mut self, columns: usize) -> Config {
+        self.width = Some(columns).filter(|&columns| columns > 0);
+        self
+    }
+
+    /// Sets the maximum width errors are wrapped to.
+    pub fn max_width(mut self, columns: usize) -> Config {
+        self.max_width = Some(columns);
+        self
+    }
+
+    /// Sets the stream errors are printed to.
+    pub fn stream(mut self, stream: Stream) -> Config {
+        self.stream = stream;
+        self
+    }
+
+    /// Sets how errors are rendered when they are printed.
+    pub fn style(mut self, style: OutputStyle) -> Config {
+        self.style = Some(style);
+        self
+    }
+
+    /// Prints only the summary of errors (true), or all of it (false).
+    pub fn quiet(mut self, quiet: bool) -> Config {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Sets the maximum number of reasons printed. The rest are counted in a
+    /// notice after the last one printed.
+    pub fn max_reasons(mut self, max: usize) -> Config {
+        self.max_reasons = Some(max);
+        self
+    }
+
+    /// Prints extra detail meant for bug reports (true), or leaves it out
+    /// (false).
+    pub fn verbose(mut self, verbose: bool) -> Config {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Sets the version of the program, printed in environment snapshots.
+    pub fn app_version<S: Into<String>>(mut self, version: S) -> Config {
+        self.app_version = Some(version.into());
+        self
+    }
+
+    /// Sets the name of the program, used to tell the user how to see what
+    /// was left out of an error.
+    pub fn app_name<S: Into<String>>(mut self, name: S) -> Config {
+        self.app_name = Some(name.into());
+        self
+    }
+
+    /// Sets the command line flag that makes the program print everything.
+    /// "--verbose" by default.
+    pub fn verbose_flag<S: Into<String>>(mut self, flag: S) -> Config {
+        self.verbose_flag = Cow::Owned(flag.into());
+        self
+    }
+
+    /// Shows printed errors that are longer than the given number of lines
+    /// through the user's pager.
+    pub fn page_long_output(mut self, threshold_lines: usize) -> Config {
+        self.page_threshold = Some(threshold_lines);
+        self
+    }
+
+    /// Sets the link offered for reporting errors that end the program, e.g.
+    /// "https://github.com/me/mytool/issues/new?title={title}&body={body}".
+    pub fn bug_report_url<S: Into<String>>(mut self, template: S) -> Config {
+        self.bug_report_url = Some(template.into());
+        self
+    }
+
+    /// Sets the maximum number of bytes of the error put in the bug report
+    /// link. 2000 by default.
+    pub fn bug_report_max_bytes(mut self, max: usize) -> Config {
+        self.bug_report_max_bytes = max;
+        self
+    }
+
+    // The name of the program: the one set, or else the one it was run with
+    pub(crate) fn program_name(&self) -> Option<String> {
+        self.app_name.clone().or_else(|| {
+            std::env::args_os()
+                .next()
+                .as_deref()
+                .map(std::path::Path::new)
+                .and_then(std::path::Path::file_stem)
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+    }
+
+    // The command the user can run to see everything, e.g. `mytool --verbose`
+    pub(crate) fn verbose_command(&self) -> String {
+        match self.program_name() {
+            Some(app) => format!("`{} {}`", app, self.verbose_flag),
+            None => format!("`{}`", self.verbose_flag),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::new()
+    }
+}
+
+// The installed configuration
+static CONFIG: RwLock<Config> = RwLock::new(Config::new());
+
+/// Installs a configuration, replacing every option at once.
+pub fn configure(config: Config) {
+    update(|current| *current = config);
+}
+
+// Reads from the installed configuration
+pub(crate) fn read<T>(f: impl FnOnce(&Config) -> T) -> T {
+    let config = CONFIG.read().unwrap_or_else(|e| e.into_inner());
+    f(&config)
+}
+
+// Changes the installed configuration. The lock is held throughout, so that
+// concurrent changes to different options don't undo each other.
+pub(crate) fn update(f: impl FnOnce(&mut Config)) {
+    let mut config = CONFIG.write().unwrap_or_else(|e| e.into_inner());
+    f(&mut config);
+}
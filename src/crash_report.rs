@@ -0,0 +1,169 @@
+// Detailed reports of errors that end the program, written to a file for the
+// user to attach to a bug report.
+
+use crate::theme::Theme;
+use crate::{config, environment, invocation, timestamp, UFE};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The number of names tried for a report before giving up
+const MAX_ATTEMPTS: usize = 100;
+
+// The directory reports are written to, if they are enabled
+static DIRECTORY: Mutex<Option<PathBuf>> = Mutex::new(None);
+// The number of report names this process has tried, so that no two are alike
+static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Makes print_and_exit() write a detailed report of the error to a new
+/// file in `dir` (or the temporary directory if None), and tell the user to
+/// attach it when filing a bug. The report holds the error without any
+/// styling, the debug representation of each error in its source chain, the
+/// command line the program was run with (unless turned off with
+/// [`disable_invocation()`](crate::disable_invocation)), a snapshot of the
+/// environment and the time. If the file can't be written, the error is
+/// printed as usual.
+/// # Example
+/// ```
+/// user_error::enable_crash_reports(None);
+/// ```
+pub fn enable_crash_reports(dir: Option<PathBuf>) {
+    let dir = dir.unwrap_or_else(std::env::temp_dir);
+    *DIRECTORY.lock().unwrap_or_else(|e| e.into_inner()) = Some(dir);
+}
+
+// Writes a report of the error, if reports are enabled, and returns where
+pub(crate) fn write<E: UFE + ?Sized>(error: &E) -> Option<PathBuf> {
+    let dir = DIRECTORY
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()?;
+    let (app, version) = config::read(|config| (config.program_name(), config.app_version.clone()));
+    let report = render(
+        error,
+        version.as_deref(),
+        invocation::for_report(),
+        SystemTime::now(),
+    );
+    let prefix = app.unwrap_or_else(|| "crash".to_string());
+    create(&dir, &prefix, &report).ok()
+}
+
+// The report of the error, as plain text
+fn render<E: UFE + ?Sized>(
+    error: &E,
+    version: Option<&str>,
+    invocation: Option<String>,
+    time: SystemTime,
+) -> String {
+    let mut sections = vec![
+        format!("Crash report ({})", timestamp::rfc3339(time)),
+        error.render_plain().trim_end().to_string(),
+    ];
+    let mut chain = Vec::new();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        chain.push(format!("  {:?}", cause));
+        source = cause.source();
+    }
+    if !chain.is_empty() {
+        sections.push(["Source chain:".to_string(), chain.join("\n")].join("\n"));
+    }
+    let invocation = invocation.unwrap_or_else(|| "(not captured)".to_string());
+    sections.push(format!("Invocation: {}", invocation));
+    sections.push(environment::render(&[], version, |_| None, &Theme::plain()));
+    sections.join("\n\n") + "\n"
+}
+
+// Writes the report to a file named after the program that didn't exist
+// before, e.g. "mytool-report-4242-1704110400000000000-0.txt"
+fn create(dir: &Path, prefix: &str, report: &str) -> io::Result<PathBuf> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos());
+    for _ in 0..MAX_ATTEMPTS {
+        let attempt = ATTEMPTS.fetch_add(1, Ordering::Relaxed);
+        let name = format!(
+            "{}-report-{}-{}-{}.txt",
+            prefix,
+            std::process::id(),
+            nanos,
+            attempt
+        );
+        let path = dir.join(name);
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        };
+        /* Don't leave half a report behind */
+        if let Err(e) = file.write_all(report.as_bytes()) {
+            let _ = fs::remove_file(&path);
+            return Err(e);
+        }
+        return Ok(path);
+    }
+    Err(io::Error::from(io::ErrorKind::AlreadyExists))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::strip_ansi;
+    use crate::{pretty_crash_report, UserFacingError};
+
+    #[test]
+    fn crash_report_test() {
+        crate::tests::pin_rendering();
+        let dir = std::env::temp_dir().join(format!("user-error-reports-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        enable_crash_reports(Some(dir.clone()));
+
+        let ioe = io::Error::from(io::ErrorKind::NotFound);
+        let e = UserFacingError::new("Failed to sync")
+            .reason("Profile not found")
+            .with_source(ioe);
+        let first = write(&e).unwrap();
+        let second = write(&e).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(first.parent(), Some(dir.as_path()));
+
+        let report = fs::read_to_string(&first).unwrap();
+        assert!(report.starts_with("Crash report ("));
+        assert!(report
+            .contains("\n\nError: Failed to sync\n - Profile not found\n - entity not found\n\n"));
+        assert!(report.contains("\n\nSource chain:\n  Kind(NotFound)\n\n"));
+        assert!(report.contains("\n\nInvocation: "));
+        assert!(report.contains("\n\nEnvironment:\n  os: "));
+
+        /* The user is told where to find it */
+        let notice = strip_ansi(&pretty_crash_report(&first)).replace('\n', " ");
+        let expected = format!(
+            "A detailed report was written to {} \u{2014} please attach it when filing a bug.",
+            first.display()
+        );
+        assert_eq!(notice, expected);
+
+        /* Nothing is written where it can't be */
+        enable_crash_reports(Some(dir.join("missing")));
+        assert!(write(&e).is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn render_test() {
+        let e = UserFacingError::new("Failed to sync");
+        let time = UNIX_EPOCH + std::time::Duration::from_secs(1_704_110_400);
+        let report = render(&e, Some("mytool 1.2.0"), Some("mytool sync".into()), time);
+        let expected = format!(
+            "Crash report (2024-01-01T12:00:00Z)\n\nError: Failed to sync\n\n\
+             Invocation: mytool sync\n\nEnvironment:\n  version: mytool 1.2.0\n  os: {} ({})\n",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        assert_eq!(report, expected);
+    }
+}
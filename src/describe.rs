@@ -0,0 +1,53 @@
+// Describing an error without printing it, e.g. to hand it to a logger.
+
+use crate::terminal::strip_ansi;
+use crate::UFE;
+use std::fmt::{self, Debug, Display};
+
+/// A description of an error that is only rendered when it is formatted.
+/// Displays as the pretty printed error (with colors), and debug formats as
+/// the same text without any colors. Created with
+/// [`UFE::describe()`](crate::UFE::describe).
+/// # Example
+/// ```
+/// use user_error::prelude::*;
+/// let err = UserFacingError::new("File failed to open");
+/// let message = format!("{:?}", err.describe());
+/// assert_eq!(message, "Error: File failed to open");
+/// ```
+pub struct Description<'a, E: UFE + ?Sized> {
+    error: &'a E,
+}
+
+impl<'a, E: UFE + ?Sized> Description<'a, E> {
+    pub(crate) fn new(error: &'a E) -> Description<'a, E> {
+        Description { error }
+    }
+}
+
+impl<E: UFE + ?Sized> Display for Description<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.error.to_pretty_string())
+    }
+}
+
+impl<E: UFE + ?Sized> Debug for Description<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&strip_ansi(&self.error.to_pretty_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{UserFacingError, UFE};
+
+    #[test]
+    fn describe_test() {
+        let e = UserFacingError::new("Test Error").reason("Reason 1");
+        assert_eq!(e.describe().to_string(), e.to_pretty_string());
+        assert_eq!(
+            format!("{:?}", e.describe()),
+            "Error: Test Error\n - Reason 1\n"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+// Sending errors as desktop notifications, for when nobody is watching the
+// terminal. Enabled by the "desktop-notifications" feature.
+
+use crate::redact::redact;
+use crate::terminal::strip_ansi;
+use crate::UFE;
+use std::error::Error;
+
+// Something that can show a notification with a title and a body
+pub(crate) trait Notifier {
+    fn show(&self, title: &str, body: &str) -> Result<(), Box<dyn Error>>;
+}
+
+// The platform's own notifications
+struct Desktop;
+
+impl Notifier for Desktop {
+    fn show(&self, title: &str, body: &str) -> Result<(), Box<dyn Error>> {
+        notify_rust::Notification::new()
+            .summary(title)
+            .body(body)
+            .show()?;
+        Ok(())
+    }
+}
+
+// The title and body of the notification for an error: its summary, and its
+// first reason (if any), without any styling and with secrets redacted
+fn compose<E: UFE + ?Sized>(error: &E) -> (String, String) {
+    let plain = |text: &str| strip_ansi(&redact(text));
+    let title = plain(&error.summary());
+    let body = error
+        .reasons()
+        .and_then(|reasons| reasons.into_iter().next())
+        .map_or_else(String::new, |reason| plain(&reason));
+    (title, body)
+}
+
+// Shows the error with the notifier. A notification that can't be shown
+// isn't worth failing over, so any failure is ignored.
+fn notify_with<E: UFE + ?Sized>(error: &E, notifier: &dyn Notifier) {
+    let (title, body) = compose(error);
+    let _ = notifier.show(&title, &body);
+}
+
+// Shows the error as a desktop notification
+pub(crate) fn notify<E: UFE + ?Sized>(error: &E) {
+    notify_with(error, &Desktop);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UserFacingError;
+    use std::cell::RefCell;
+
+    // Remembers what it was asked to show, then fails
+    struct Recorder(RefCell<Vec<(String, String)>>);
+
+    impl Notifier for Recorder {
+        fn show(&self, title: &str, body: &str) -> Result<(), Box<dyn Error>> {
+            self.0.borrow_mut().push((title.into(), body.into()));
+            Err("no notification server".into())
+        }
+    }
+
+    #[test]
+    fn notify_with_test() {
+        let recorder = Recorder(RefCell::new(Vec::new()));
+        let e = UserFacingError::new("Failed to build project")
+            .reason("main.db not found")
+            .reason("config.toml not found");
+        notify_with(&e, &recorder);
+        notify_with(&UserFacingError::new("Failed to deploy"), &recorder);
+
+        assert_eq!(
+            *recorder.0.borrow(),
+            vec![
+                ("Failed to build project".into(), "main.db not found".into()),
+                ("Failed to deploy".into(), String::new()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,41 @@
+// Conversions from the standard library's errors about invalid text.
+
+use std::borrow::Cow;
+use std::char::CharTryFromError;
+
+use crate::{messages, Reason, UserFacingError};
+
+/// Allows you to create UserFacingErrors From CharTryFromErrors, returned
+/// when a number is not a valid Unicode character.
+/// # Example
+/// ```
+/// # use user_error::UserFacingError;
+/// use std::convert::TryFrom;
+/// let ufe: UserFacingError = char::try_from(0xD800u32).unwrap_err().into();
+/// ```
+impl From<CharTryFromError> for UserFacingError {
+    fn from(error: CharTryFromError) -> UserFacingError {
+        UserFacingError {
+            reasons: Some(vec![Reason::from(messages::message("invalid-char-reason"))]),
+            helptext: Some(Cow::Owned(messages::message("invalid-char-help"))),
+            source: Some(Box::new(error)),
+            ..UserFacingError::new(messages::message("invalid-char-summary"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{UserFacingError, UFE};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn char_try_from_test() {
+        let error = char::try_from(0xD800u32).unwrap_err();
+        let ufe = UserFacingError::from(error);
+        assert_eq!(ufe.summary(), "Invalid Unicode character");
+        assert!(ufe.reasons().unwrap()[0].contains("surrogates"));
+        assert_eq!(ufe.helptext().unwrap(), "Ensure the input is valid UTF-32");
+        assert!(ufe.source.is_some());
+    }
+}
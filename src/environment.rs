@@ -0,0 +1,47 @@
+// A snapshot of the environment the program ran in, for bug reports.
+
+use crate::redact::redact;
+use crate::theme::Theme;
+use std::env::consts::{ARCH, OS};
+use std::ffi::OsString;
+
+// Shown in place of the value of an environment variable that isn't set
+const UNSET: &str = "(unset)";
+
+/// Renders the version of the program (if known), the operating system and
+/// architecture, then the value of each environment variable, looked up with
+/// `var`.
+pub(crate) fn render(
+    keys: &[String],
+    version: Option<&str>,
+    var: impl Fn(&str) -> Option<OsString>,
+    theme: &Theme,
+) -> String {
+    let mut lines = vec!["Environment:".to_string()];
+    if let Some(version) = version {
+        lines.push(format!("  version: {}", version));
+    }
+    lines.push(format!("  os: {} ({})", OS, ARCH));
+    for key in keys {
+        let value = var(key).map(|value| redact(&value.to_string_lossy()).into_owned());
+        lines.push(format!("  {}: {}", key, value.as_deref().unwrap_or(UNSET)));
+    }
+    [&*theme.footer, &*lines.join("\n"), &*theme.reset].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environment_test() {
+        let keys = vec!["EDITOR".to_string(), "TOKEN".to_string()];
+        let var = |key: &str| Some(OsString::from("vim")).filter(|_| key == "EDITOR");
+        let rendered = render(&keys, Some("1.2.0"), var, &Theme::plain());
+        let expected = format!(
+            "Environment:\n  version: 1.2.0\n  os: {} ({})\n  EDITOR: vim\n  TOKEN: (unset)",
+            OS, ARCH
+        );
+        assert_eq!(rendered, expected);
+    }
+}
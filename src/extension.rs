@@ -0,0 +1,77 @@
+// Converting any error into a UserFacingError with a method call, for errors
+// that have no From conversion of their own.
+
+use crate::UserFacingError;
+use std::error::Error;
+
+/// Converts any error into a UserFacingError: its message becomes the
+/// summary, the errors in its source chain become the reasons, and the error
+/// itself is kept as the source. A UserFacingError is returned unchanged.
+///
+/// There is no blanket `impl<E: Error> From<E> for UserFacingError`, which
+/// would let `?` do this on its own, because it isn't allowed: it overlaps
+/// with the standard library's `impl<T> From<T> for T` (UserFacingError is
+/// itself an Error), and with the conversions for specific errors such as
+/// `std::io::Error` that add help text.
+///
+/// The method isn't called `into_ufe()` so that it doesn't take the place of
+/// [`UFE::into_ufe()`](crate::UFE::into_ufe), which keeps the help text of
+/// types that implement UFE, wherever both traits are in scope.
+/// # Example
+/// ```
+/// use user_error::{IntoUserFacing, UserFacingError};
+/// fn parse(port: &str) -> Result<u16, UserFacingError> {
+///     port.parse().map_err(|e: std::num::ParseIntError| e.into_user_facing())
+/// }
+/// ```
+pub trait IntoUserFacing {
+    /// Converts the error into a UserFacingError.
+    fn into_user_facing(self) -> UserFacingError;
+}
+
+impl<E: Error + 'static> IntoUserFacing for E {
+    fn into_user_facing(self) -> UserFacingError {
+        let error: Box<dyn Error> = Box::new(self);
+        match error.downcast::<UserFacingError>() {
+            Ok(ufe) => *ufe,
+            Err(error) => UserFacingError::from(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UFE;
+    use std::fmt::{self, Display};
+
+    #[derive(Debug)]
+    struct ConfigError(std::io::Error);
+
+    impl Display for ConfigError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "Failed to load config")
+        }
+    }
+
+    impl Error for ConfigError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn into_user_facing_test() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "config.toml not found");
+        let ufe = ConfigError(io).into_user_facing();
+        assert_eq!(ufe.summary(), "Failed to load config");
+        assert_eq!(ufe.reasons().unwrap(), vec!["config.toml not found"]);
+        assert!(ufe.source_as::<ConfigError>().is_some());
+
+        let ufe = UserFacingError::new("Test Error")
+            .help("Try Again")
+            .into_user_facing();
+        assert_eq!(ufe.summary(), "Test Error");
+        assert_eq!(ufe.helptext().unwrap(), "Try Again");
+    }
+}
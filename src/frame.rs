@@ -0,0 +1,116 @@
+// Drawing a box around a rendered error, to make it stand out.
+
+use crate::terminal;
+use crate::wrap::display_width;
+use std::cell::Cell;
+
+// Columns the box takes up on either side of the text: the border and a space
+pub(crate) const FRAME_WIDTH: usize = 4;
+
+/// The characters a box is drawn with: corners (top left, top right, bottom
+/// left, bottom right), then the horizontal and vertical lines.
+struct Borders {
+    corners: [char; 4],
+    horizontal: char,
+    vertical: char,
+}
+
+const UNICODE: Borders = Borders {
+    corners: ['\u{250c}', '\u{2510}', '\u{2514}', '\u{2518}'],
+    horizontal: '\u{2500}',
+    vertical: '\u{2502}',
+};
+
+const ASCII: Borders = Borders {
+    corners: ['+', '+', '+', '+'],
+    horizontal: '-',
+    vertical: '|',
+};
+
+/// Draws a box around rendered text, padding every line to the width of the
+/// widest one. ANSI escape codes are not counted towards the width. Drawn
+/// with ASCII characters if `ascii` is set.
+pub(crate) fn frame(text: &str, ascii: bool) -> String {
+    let borders = if ascii { &ASCII } else { &UNICODE };
+    let lines: Vec<&str> = text.lines().collect();
+    let width = lines
+        .iter()
+        .map(|line| display_width(&terminal::strip_ansi(line)))
+        .max()
+        .unwrap_or(0);
+
+    let horizontal = borders.horizontal.to_string().repeat(width + 2);
+    let [top_left, top_right, bottom_left, bottom_right] = borders.corners;
+    let mut framed = vec![format!("{}{}{}", top_left, horizontal, top_right)];
+    for line in lines {
+        let padding = width - display_width(&terminal::strip_ansi(line));
+        framed.push(format!(
+            "{} {}{} {}",
+            borders.vertical,
+            line,
+            " ".repeat(padding),
+            borders.vertical
+        ));
+    }
+    framed.push(format!("{}{}{}", bottom_left, horizontal, bottom_right));
+    [framed.join("\n"), "\n".to_string()].concat()
+}
+
+thread_local! {
+    // Columns taken away from the wrap width while rendering inside a box
+    static RESERVED: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Renders text to fit inside a box: the wrap width is narrowed by the width
+/// of the box while `render` runs.
+pub(crate) fn narrowed<T>(render: impl FnOnce() -> T) -> T {
+    let previous = RESERVED.with(|reserved| reserved.replace(FRAME_WIDTH));
+    let rendered = render();
+    RESERVED.with(|reserved| reserved.set(previous));
+    rendered
+}
+
+/// The columns taken away from the wrap width on this thread.
+pub(crate) fn reserved() -> usize {
+    RESERVED.with(Cell::get)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_test() {
+        let text = "\u{001b}[91mError: Failed\u{001b}[0m\n - Reason 1\nTry Again\n";
+        let framed = frame(text, false);
+        let lines: Vec<&str> = framed.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(
+            lines[0],
+            format!("\u{250c}{}\u{2510}", "\u{2500}".repeat(15))
+        );
+        assert_eq!(lines[2], "\u{2502}  - Reason 1   \u{2502}");
+        assert_eq!(
+            lines[4],
+            format!("\u{2514}{}\u{2518}", "\u{2500}".repeat(15))
+        );
+        let widths: Vec<usize> = lines
+            .iter()
+            .map(|line| display_width(&terminal::strip_ansi(line)))
+            .collect();
+        assert!(widths.iter().all(|&width| width == 17));
+
+        let framed = frame("Error: Failed\n", true);
+        assert_eq!(
+            framed,
+            "+---------------+\n| Error: Failed |\n+---------------+\n"
+        );
+    }
+
+    #[test]
+    fn narrowed_test() {
+        assert_eq!(reserved(), 0);
+        assert_eq!(narrowed(reserved), FRAME_WIDTH);
+        assert_eq!(reserved(), 0);
+    }
+}
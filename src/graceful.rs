@@ -0,0 +1,64 @@
+// Expected ways for a program to end early, modeled as errors so that they
+// can be propagated like any other.
+
+use crate::{Stream, UFE};
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// A pseudo-error for when a program ends early on purpose, e.g. after
+/// printing its usage for `--help` or its version for `--version`. It is
+/// printed to stdout as is, without the 'Error:' label. Exit with
+/// exit_gracefully() so that the exit code is 0.
+/// # Example
+/// ```no_run
+/// use user_error::{GracefulExit, UFE};
+/// GracefulExit::new("mytool 1.0.0").exit_gracefully();
+/// ```
+#[derive(Debug)]
+pub struct GracefulExit {
+    message: String,
+}
+
+impl GracefulExit {
+    /// Creates a pseudo-error that prints the message when the program ends.
+    pub fn new<S: Into<String>>(message: S) -> GracefulExit {
+        GracefulExit {
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for GracefulExit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for GracefulExit {}
+
+impl UFE for GracefulExit {
+    fn stream(&self) -> Stream {
+        Stream::Stdout
+    }
+    // Nothing went wrong, so the message is printed without any styling
+    fn to_pretty_string(&self) -> String {
+        [&self.message, "\n"].concat()
+    }
+    // ...and neither quiet nor JSON output leave it out
+    fn to_printed_string(&self) -> String {
+        self.to_pretty_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graceful_exit_test() {
+        let exit = GracefulExit::new("usage: mytool [FILE]");
+        assert_eq!(exit.to_pretty_string(), "usage: mytool [FILE]\n");
+        assert_eq!(exit.to_printed_string(), "usage: mytool [FILE]\n");
+        assert_eq!(exit.stream(), Stream::Stdout);
+    }
+}
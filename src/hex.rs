@@ -0,0 +1,33 @@
+// Hexadecimal encoding of bytes, for passing errors around as short codes.
+
+/// Encodes the bytes as lowercase hexadecimal, two digits per byte.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decodes hexadecimal (in either case) back into bytes. Returns None if
+/// there is an odd number of digits, or anything that isn't a digit.
+pub(crate) fn decode(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_test() {
+        assert_eq!(encode(b"{\"a\"}"), "7b2261227d");
+        assert_eq!(decode("7B2261227d"), Some(b"{\"a\"}".to_vec()));
+        assert_eq!(decode(""), Some(Vec::new()));
+        assert_eq!(decode("7b2"), None);
+        assert_eq!(decode("zz"), None);
+        assert_eq!(decode("+1"), None);
+    }
+}
@@ -0,0 +1,57 @@
+// Making keywords stand out within the text of an error.
+
+/// Styles every occurrence of the patterns in the text with `accent`, and
+/// goes back to the `surrounding` style after each one. Where patterns
+/// overlap, the one that starts first wins, and of those the one listed first.
+pub(crate) fn highlight(
+    text: &str,
+    patterns: &[String],
+    accent: &str,
+    surrounding: &str,
+) -> String {
+    if patterns.is_empty() || accent.is_empty() {
+        return text.to_string();
+    }
+
+    let mut highlighted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let pattern = patterns
+            .iter()
+            .find(|pattern| !pattern.is_empty() && rest.starts_with(pattern.as_str()));
+        match pattern {
+            Some(pattern) => {
+                highlighted.push_str(accent);
+                highlighted.push_str(pattern);
+                highlighted.push_str(surrounding);
+                rest = &rest[pattern.len()..];
+            }
+            None => {
+                highlighted.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    highlighted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_test() {
+        let patterns = vec!["main.db".to_string(), "db".to_string()];
+        assert_eq!(
+            highlight("File main.db not found", &patterns, "<", ">"),
+            "File <main.db> not found"
+        );
+        assert_eq!(highlight("a db", &patterns, "<", ">"), "a <db>");
+        assert_eq!(highlight("a db", &[], "<", ">"), "a db");
+        assert_eq!(highlight("a db", &patterns, "", ""), "a db");
+
+        /* The pattern that starts first wins over one listed first */
+        let patterns = vec!["db".to_string(), "main.d".to_string()];
+        assert_eq!(highlight("main.db", &patterns, "<", ">"), "<main.d>b");
+    }
+}
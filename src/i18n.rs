@@ -0,0 +1,93 @@
+//! # Localization
+//! Every piece of text this crate generates on its own (the "Error:" label,
+//! notices about reasons that were left out, etc.) is looked up by a stable
+//! identifier. Install a [`MessageProvider`] with [`set_messages`] to translate
+//! them. Any identifier the provider does not know falls back to English.
+//!
+//! The help text and reasons added to errors converted from `std::io::Error`
+//! are looked up as `io-help-` and `io-reason-` followed by the kind of
+//! error in kebab case, e.g. `io-help-not-found` or
+//! `io-reason-read-only-filesystem`. The text of errors converted from other
+//! errors is looked up by the name of the error followed by the part of it,
+//! e.g. `invalid-char-summary`, `regex-help-too-big` or `semver-help`.
+//!
+//! # Example
+//! ```
+//! use user_error::i18n::{self, MessageProvider};
+//!
+//! struct French;
+//!
+//! impl MessageProvider for French {
+//!     fn message(&self, id: &str) -> Option<String> {
+//!         match id {
+//!             i18n::ERROR_LABEL => Some("Erreur :".into()),
+//!             _ => None,
+//!         }
+//!     }
+//!
+//!     fn plural(&self, id: &str, count: usize) -> Option<String> {
+//!         match (id, count) {
+//!             (i18n::MORE_REASONS, 0..=1) => Some("\u{2026} et {count} autre".into()),
+//!             (i18n::MORE_REASONS, _) => Some("\u{2026} et {count} autres".into()),
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! i18n::set_messages(French);
+//! ```
+
+use std::sync::RwLock;
+
+pub use crate::messages::{
+    message, plural, BUG_REPORT, CRASH_REPORT, DEFAULT_SUMMARY, DOC_URL, ERRORS_OCCURRED,
+    ERROR_AGE, ERROR_LABEL, HIDDEN_DETAILS, MORE_REASONS, RUN_SUMMARY, SUGGEST_COMMAND,
+    SUGGEST_COMMANDS, TRY_COMMAND,
+};
+
+/// A catalog of translated messages, keyed by the identifiers exported from
+/// this module.
+pub trait MessageProvider: Send + Sync {
+    /// Returns the translation of the message with the given identifier, or
+    /// None to fall back to English.
+    fn message(&self, id: &str) -> Option<String>;
+
+    /// Returns the translation of a pluralized message with the given
+    /// identifier in the plural form appropriate for `count`, or None to fall
+    /// back to English. Any `{count}` in the returned text is replaced by the
+    /// count.
+    fn plural(&self, id: &str, count: usize) -> Option<String> {
+        let _ = count;
+        self.message(id)
+    }
+}
+
+// The installed catalog (if any)
+static CATALOG: RwLock<Option<Box<dyn MessageProvider>>> = RwLock::new(None);
+
+/// Installs a message catalog, replacing the previous one (if any).
+pub fn set_messages(provider: impl MessageProvider + 'static) {
+    let mut catalog = CATALOG.write().unwrap_or_else(|e| e.into_inner());
+    *catalog = Some(Box::new(provider));
+}
+
+/// Removes the installed message catalog so that the built-in English text
+/// is used again.
+pub fn clear_messages() {
+    let mut catalog = CATALOG.write().unwrap_or_else(|e| e.into_inner());
+    *catalog = None;
+}
+
+// Looks up a message in the installed catalog (if any)
+pub(crate) fn lookup(id: &str) -> Option<String> {
+    let catalog = CATALOG.read().unwrap_or_else(|e| e.into_inner());
+    catalog.as_ref().and_then(|catalog| catalog.message(id))
+}
+
+// Looks up a pluralized message in the installed catalog (if any)
+pub(crate) fn lookup_plural(id: &str, count: usize) -> Option<String> {
+    let catalog = CATALOG.read().unwrap_or_else(|e| e.into_inner());
+    catalog
+        .as_ref()
+        .and_then(|catalog| catalog.plural(id, count))
+}
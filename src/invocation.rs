@@ -0,0 +1,132 @@
+// The command line the program was run with, for bug reports. Left out unless
+// turned on, as it may hold private data.
+
+use crate::redact::redact;
+use crate::theme::Theme;
+use std::sync::Mutex;
+
+// Whether the invocation is included in errors (None until it is turned on or
+// off), and the arguments it is made of once they are known
+struct Capture {
+    enabled: Option<bool>,
+    args: Option<Vec<String>>,
+}
+
+static CAPTURE: Mutex<Capture> = Mutex::new(Capture {
+    enabled: None,
+    args: None,
+});
+
+// Changes the capture. Not worth panicking over, so a poisoned lock is used
+// as is.
+fn update<T>(f: impl FnOnce(&mut Capture) -> T) -> T {
+    let mut capture = CAPTURE.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut capture)
+}
+
+/// Includes the command line the program was run with (e.g. "invocation:
+/// mytool sync --force ./data") in verbose output and in JSON, so that bug
+/// reports say exactly how the program was run. The arguments are read the
+/// first time they are needed, and secrets in them are redacted.
+/// # Example
+/// ```
+/// user_error::capture_invocation();
+/// ```
+pub fn capture_invocation() {
+    update(|capture| capture.enabled = Some(true));
+}
+
+/// Includes the given command line instead of the one the program was run
+/// with, e.g. one with private arguments left out. Turns capturing on, as
+/// capture_invocation() does.
+/// # Example
+/// ```
+/// user_error::set_invocation(&["mytool", "sync", "--force"]);
+/// ```
+pub fn set_invocation<S: AsRef<str>>(args: &[S]) {
+    update(|capture| {
+        capture.enabled = Some(true);
+        capture.args = Some(args.iter().map(|arg| arg.as_ref().to_string()).collect());
+    });
+}
+
+/// Leaves the command line out of errors again, and out of crash reports,
+/// e.g. for deployments where it might be private.
+/// # Example
+/// ```
+/// user_error::disable_invocation();
+/// ```
+pub fn disable_invocation() {
+    update(|capture| capture.enabled = Some(false));
+}
+
+/// The command line, with secrets redacted, if capturing is turned on.
+pub(crate) fn current() -> Option<String> {
+    captured(|enabled| enabled == Some(true))
+}
+
+/// The command line for a crash report, with secrets redacted, unless
+/// capturing was turned off.
+pub(crate) fn for_report() -> Option<String> {
+    captured(|enabled| enabled != Some(false))
+}
+
+// The command line, with secrets redacted, if it is included given whether
+// capturing was turned on or off
+fn captured(included: fn(Option<bool>) -> bool) -> Option<String> {
+    let args = update(|capture| {
+        if !included(capture.enabled) {
+            return None;
+        }
+        let args = capture.args.get_or_insert_with(|| {
+            std::env::args_os()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect()
+        });
+        Some(args.clone())
+    })?;
+    Some(redact(&command_line(&args)).into_owned())
+}
+
+// Joins the arguments into a command line, quoting those that wouldn't come
+// through a shell as they are
+fn command_line(args: &[String]) -> String {
+    let quoted: Vec<String> = args
+        .iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '\'') {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            } else {
+                arg.clone()
+            }
+        })
+        .collect();
+    quoted.join(" ")
+}
+
+/// Renders the command line as a dim line.
+pub(crate) fn render(invocation: &str, theme: &Theme) -> String {
+    [&*theme.footer, "invocation: ", invocation, &*theme.reset].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_test() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            command_line(&args(&["mytool", "sync", "--force", "./data"])),
+            "mytool sync --force ./data"
+        );
+        assert_eq!(
+            command_line(&args(&["mytool", "my file", "", "it's"])),
+            r"mytool 'my file' '' 'it'\''s'"
+        );
+        assert_eq!(
+            render("mytool sync", &Theme::plain()),
+            "invocation: mytool sync"
+        );
+    }
+}
@@ -0,0 +1,436 @@
+// Minimal JSON encoding of errors, for machine readable output, and decoding
+// of what was encoded.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Encodes the text as a JSON string, quotes included.
+pub(crate) fn string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Encodes the value on a single line, without any whitespace.
+pub(crate) fn compact(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(number) => number.clone(),
+        Value::String(text) => string(text),
+        Value::Array(values) => {
+            let values: Vec<String> = values.iter().map(compact).collect();
+            ["[", &values.join(","), "]"].concat()
+        }
+        Value::Object(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(key, value)| [string(key), ":".to_string(), compact(value)].concat())
+                .collect();
+            ["{", &fields.join(","), "}"].concat()
+        }
+    }
+}
+
+/// Encodes the value with each element of an array or object on its own
+/// line, indented by two spaces per level.
+pub(crate) fn pretty(value: &Value) -> String {
+    let mut json = String::new();
+    write_pretty(value, 0, &mut json);
+    json
+}
+
+// Appends the value to the JSON, indented as though it were `level` deep
+fn write_pretty(value: &Value, level: usize, json: &mut String) {
+    let indent = |level: usize| "  ".repeat(level);
+    match value {
+        Value::Null => json.push_str("null"),
+        Value::Bool(value) => json.push_str(if *value { "true" } else { "false" }),
+        Value::Number(number) => json.push_str(number),
+        Value::String(text) => json.push_str(&string(text)),
+        Value::Array(values) if values.is_empty() => json.push_str("[]"),
+        Value::Object(fields) if fields.is_empty() => json.push_str("{}"),
+        Value::Array(values) => {
+            json.push_str("[\n");
+            for (i, value) in values.iter().enumerate() {
+                json.push_str(&indent(level + 1));
+                write_pretty(value, level + 1, json);
+                json.push_str(if i + 1 < values.len() { ",\n" } else { "\n" });
+            }
+            json.push_str(&indent(level));
+            json.push(']');
+        }
+        Value::Object(fields) => {
+            json.push_str("{\n");
+            for (i, (key, value)) in fields.iter().enumerate() {
+                json.push_str(&indent(level + 1));
+                json.push_str(&string(key));
+                json.push_str(": ");
+                write_pretty(value, level + 1, json);
+                json.push_str(if i + 1 < fields.len() { ",\n" } else { "\n" });
+            }
+            json.push_str(&indent(level));
+            json.push('}');
+        }
+    }
+}
+
+/// A JSON value. Numbers are kept as they were written, since errors are
+/// encoded without any and only need to skip them when decoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// The value of a field, if this is an object that has it.
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// The text, if this is a string.
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+// How deeply arrays and objects may be nested, so that decoding untrusted
+// input can't overflow the stack
+const MAX_DEPTH: usize = 128;
+
+/// Decodes a JSON value. Returns None if it isn't valid, or nests arrays and
+/// objects more than MAX_DEPTH deep.
+pub(crate) fn parse(json: &str) -> Option<Value> {
+    let mut parser = Parser {
+        chars: json.chars().peekable(),
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    match parser.chars.next() {
+        Some(_) => None,
+        None => Some(value),
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    // How many arrays and objects the parser is inside of
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    // Consumes the character (after any whitespace), if it is next
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if_eq(&expected).is_some()
+    }
+
+    // Consumes the word, if it is next
+    fn literal(&mut self, word: &str) -> bool {
+        let next: String = self.chars.by_ref().take(word.len()).collect();
+        next == word
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip_whitespace();
+        match self.chars.peek()? {
+            '"' => self.string().map(Value::String),
+            '[' | '{' if self.depth >= MAX_DEPTH => None,
+            '[' => self.nested(Parser::array),
+            '{' => self.nested(Parser::object),
+            'n' => Some(Value::Null).filter(|_| self.literal("null")),
+            't' => Some(Value::Bool(true)).filter(|_| self.literal("true")),
+            'f' => Some(Value::Bool(false)).filter(|_| self.literal("false")),
+            '-' | '0'..='9' => self.number().map(Value::Number),
+            _ => None,
+        }
+    }
+
+    // Parses an array or object one level deeper
+    fn nested(&mut self, parse: fn(&mut Self) -> Option<Value>) -> Option<Value> {
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn number(&mut self) -> Option<String> {
+        let mut number = String::new();
+        if let Some(minus) = self.chars.next_if_eq(&'-') {
+            number.push(minus);
+        }
+        match self.chars.next()? {
+            '0' => number.push('0'),
+            c @ '1'..='9' => {
+                number.push(c);
+                self.digits(&mut number);
+            }
+            _ => return None,
+        }
+        if let Some(point) = self.chars.next_if_eq(&'.') {
+            number.push(point);
+            if !self.digits(&mut number) {
+                return None;
+            }
+        }
+        if let Some(e) = self.chars.next_if(|c| *c == 'e' || *c == 'E') {
+            number.push(e);
+            if let Some(sign) = self.chars.next_if(|c| *c == '+' || *c == '-') {
+                number.push(sign);
+            }
+            if !self.digits(&mut number) {
+                return None;
+            }
+        }
+        Some(number)
+    }
+
+    // Appends the digits that are next to the number, returning whether there
+    // were any
+    fn digits(&mut self, number: &mut String) -> bool {
+        let len = number.len();
+        while let Some(digit) = self.chars.next_if(char::is_ascii_digit) {
+            number.push(digit);
+        }
+        number.len() > len
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.chars.next_if_eq(&'"')?;
+        let mut text = String::new();
+        loop {
+            match self.chars.next()? {
+                '"' => return Some(text),
+                '\\' => match self.chars.next()? {
+                    '"' => text.push('"'),
+                    '\\' => text.push('\\'),
+                    '/' => text.push('/'),
+                    'b' => text.push('\u{8}'),
+                    'f' => text.push('\u{c}'),
+                    'n' => text.push('\n'),
+                    'r' => text.push('\r'),
+                    't' => text.push('\t'),
+                    'u' => text.push(self.escaped_char()?),
+                    _ => return None,
+                },
+                c => text.push(c),
+            }
+        }
+    }
+
+    // The character of a \uXXXX escape, which may be followed by a second
+    // one for characters outside the Basic Multilingual Plane
+    fn escaped_char(&mut self) -> Option<char> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high);
+        }
+        if self.chars.next()? != '\\' || self.chars.next()? != 'u' {
+            return None;
+        }
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return None;
+        }
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let mut code = 0;
+        for _ in 0..4 {
+            code = code * 16 + self.chars.next()?.to_digit(16)?;
+        }
+        Some(code)
+    }
+
+    fn array(&mut self) -> Option<Value> {
+        self.chars.next_if_eq(&'[')?;
+        let mut values = Vec::new();
+        if self.eat(']') {
+            return Some(Value::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            if self.eat(']') {
+                return Some(Value::Array(values));
+            }
+            if !self.eat(',') {
+                return None;
+            }
+        }
+    }
+
+    fn object(&mut self) -> Option<Value> {
+        self.chars.next_if_eq(&'{')?;
+        let mut fields = Vec::new();
+        if self.eat('}') {
+            return Some(Value::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            if !self.eat(':') {
+                return None;
+            }
+            fields.push((key, self.value()?));
+            if self.eat('}') {
+                return Some(Value::Object(fields));
+            }
+            if !self.eat(',') {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_test() {
+        assert_eq!(string("plain"), "\"plain\"");
+        assert_eq!(string("a \"b\"\\\n"), "\"a \\\"b\\\"\\\\\\n\"");
+        assert_eq!(string("\u{001b}[0m"), "\"\\u001b[0m\"");
+        assert_eq!(string("caf\u{e9}"), "\"caf\u{e9}\"");
+    }
+
+    #[test]
+    fn compact_test() {
+        let value = Value::Object(vec![
+            ("summary".to_string(), Value::String("S".to_string())),
+            (
+                "reasons".to_string(),
+                Value::Array(vec![
+                    Value::String("R1".to_string()),
+                    Value::String("R2".to_string()),
+                ]),
+            ),
+            ("helptext".to_string(), Value::Null),
+        ]);
+        assert_eq!(
+            compact(&value),
+            r#"{"summary":"S","reasons":["R1","R2"],"helptext":null}"#
+        );
+        assert_eq!(parse(&compact(&value)), Some(value));
+    }
+
+    #[test]
+    fn pretty_test() {
+        let value = Value::Object(vec![
+            ("summary".to_string(), Value::String("S".to_string())),
+            (
+                "reasons".to_string(),
+                Value::Array(vec![
+                    Value::String("R1".to_string()),
+                    Value::String("R2".to_string()),
+                ]),
+            ),
+            ("helptext".to_string(), Value::Null),
+            ("empty".to_string(), Value::Array(Vec::new())),
+        ]);
+        let expected =
+            "{\n  \"summary\": \"S\",\n  \"reasons\": [\n    \"R1\",\n    \"R2\"\n  ],\n  \
+                        \"helptext\": null,\n  \"empty\": []\n}";
+        assert_eq!(pretty(&value), expected);
+        assert_eq!(parse(&pretty(&value)), Some(value));
+    }
+
+    #[test]
+    fn parse_test() {
+        let json =
+            r#"{"summary":"a \"b\"\\\n\u001b\ud83d\udca1", "reasons": ["R1"], "helptext": null}"#;
+        let value = parse(json).unwrap();
+        assert_eq!(
+            value.get("summary").and_then(Value::as_str),
+            Some("a \"b\"\\\n\u{001b}\u{1f4a1}")
+        );
+        assert_eq!(
+            value.get("reasons"),
+            Some(&Value::Array(vec![Value::String("R1".to_string())]))
+        );
+        assert_eq!(value.get("helptext"), Some(&Value::Null));
+
+        assert_eq!(
+            parse(&string("caf\u{e9}\t")).unwrap().as_str(),
+            Some("caf\u{e9}\t")
+        );
+        assert!(parse(r#"["R1",]"#).is_none());
+        assert!(parse(r#""unterminated"#).is_none());
+        assert!(parse("null null").is_none());
+    }
+
+    #[test]
+    fn number_and_bool_test() {
+        let value = parse(r#"{"code": -1.5e+3, "count": 0, "ok": true, "retry": false}"#).unwrap();
+        assert_eq!(
+            value.get("code"),
+            Some(&Value::Number("-1.5e+3".to_string()))
+        );
+        assert_eq!(value.get("count"), Some(&Value::Number("0".to_string())));
+        assert_eq!(value.get("ok"), Some(&Value::Bool(true)));
+        assert_eq!(value.get("retry"), Some(&Value::Bool(false)));
+        assert_eq!(
+            compact(&value),
+            r#"{"code":-1.5e+3,"count":0,"ok":true,"retry":false}"#
+        );
+
+        assert!(parse("01").is_none());
+        assert!(parse("1.").is_none());
+        assert!(parse("-").is_none());
+        assert!(parse("1e").is_none());
+        assert!(parse("+1").is_none());
+        assert!(parse("tru").is_none());
+        assert!(parse("falsy").is_none());
+    }
+
+    #[test]
+    fn hex_escape_test() {
+        assert_eq!(parse(r#""\u00E9""#).unwrap().as_str(), Some("\u{e9}"));
+        /* Only hex digits, and exactly four of them, are accepted */
+        assert!(parse(r#""\u+0e9""#).is_none());
+        assert!(parse(r#""\u00g9""#).is_none());
+        assert!(parse("\"\\u0\u{e9}9\"").is_none());
+        assert!(parse(r#""\u0e""#).is_none());
+    }
+
+    #[test]
+    fn depth_test() {
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(parse(&nested(MAX_DEPTH)).is_some());
+        assert!(parse(&nested(MAX_DEPTH + 1)).is_none());
+        assert!(parse(&"{\"a\":".repeat(MAX_DEPTH + 1)).is_none());
+        assert!(parse(&"[".repeat(100_000)).is_none());
+    }
+}
@@ -0,0 +1,152 @@
+// A record of the errors printed during a run, so that they can be recapped
+// at the end.
+
+use crate::{messages, UserFacingError, UFE};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// The number of errors the ledger keeps unless set otherwise.
+const DEFAULT_CAPACITY: usize = 1000;
+
+/// A lightweight copy of an error that was printed, recorded in the ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntry {
+    /// Summary of the error
+    pub summary: String,
+    /// Code identifying the error, if it has one
+    pub code: Option<String>,
+    /// When the error was printed
+    pub time: SystemTime,
+}
+
+// The errors recorded so far, oldest first, and whether to record them
+struct Ledger {
+    enabled: bool,
+    capacity: usize,
+    entries: VecDeque<LedgerEntry>,
+}
+
+static LEDGER: Mutex<Ledger> = Mutex::new(Ledger {
+    enabled: false,
+    capacity: DEFAULT_CAPACITY,
+    entries: VecDeque::new(),
+});
+
+// Changes the ledger. Recording an error is not worth panicking over, so a
+// poisoned lock is used as is.
+fn update<T>(f: impl FnOnce(&mut Ledger) -> T) -> T {
+    let mut ledger = LEDGER.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut ledger)
+}
+
+/// Records every error printed with print() from now on, so that they can
+/// be recapped with [`print_run_summary()`] or inspected with
+/// [`take_error_ledger()`]. Only the most recent 1000 are kept, unless set
+/// otherwise with [`set_error_ledger_capacity()`].
+/// # Example
+/// ```
+/// user_error::enable_error_ledger();
+/// ```
+pub fn enable_error_ledger() {
+    update(|ledger| ledger.enabled = true);
+}
+
+/// Sets how many errors the ledger keeps. Once it is full, the oldest error
+/// is dropped to make room for each new one.
+/// # Example
+/// ```
+/// user_error::set_error_ledger_capacity(50);
+/// ```
+pub fn set_error_ledger_capacity(capacity: usize) {
+    update(|ledger| {
+        ledger.capacity = capacity;
+        let excess = ledger.entries.len().saturating_sub(capacity);
+        ledger.entries.drain(..excess);
+    });
+}
+
+/// Returns the errors recorded so far, oldest first, and empties the
+/// ledger.
+/// # Example
+/// ```
+/// let printed = user_error::take_error_ledger();
+/// ```
+pub fn take_error_ledger() -> Vec<LedgerEntry> {
+    update(|ledger| ledger.entries.drain(..).collect())
+}
+
+/// Prints a recap of the errors recorded so far, e.g. "3 errors occurred
+/// during this run" followed by the summary of each. Prints nothing if no
+/// errors were recorded. The ledger is left as it is.
+/// # Example
+/// ```
+/// user_error::print_run_summary();
+/// ```
+pub fn print_run_summary() {
+    let entries: Vec<LedgerEntry> = update(|ledger| ledger.entries.iter().cloned().collect());
+    if let Some(recap) = run_summary(&entries) {
+        /* Printed directly so that the recap isn't recorded itself */
+        recap.stream().print_paged(&recap.to_printed_string());
+    }
+}
+
+// The recap of the errors, each listed by its code (if any) and summary
+fn run_summary(entries: &[LedgerEntry]) -> Option<UserFacingError> {
+    if entries.is_empty() {
+        return None;
+    }
+    let summary = messages::plural(messages::RUN_SUMMARY, entries.len());
+    let reasons = entries.iter().map(|entry| match &entry.code {
+        Some(code) => format!("[{}] {}", code, entry.summary),
+        None => entry.summary.clone(),
+    });
+    let mut recap = UserFacingError::new(summary);
+    recap.extend(reasons.collect::<Vec<String>>());
+    Some(recap)
+}
+
+// Records the error in the ledger, if it is enabled
+pub(crate) fn record<E: UFE + ?Sized>(error: &E) {
+    if !update(|ledger| ledger.enabled) {
+        return;
+    }
+    /* Not copied with the lock held, in case it prints an error itself */
+    let entry = LedgerEntry {
+        summary: error.summary(),
+        code: error.code(),
+        time: SystemTime::now(),
+    };
+    update(|ledger| {
+        if ledger.capacity == 0 {
+            return;
+        }
+        if ledger.entries.len() >= ledger.capacity {
+            ledger.entries.pop_front();
+        }
+        ledger.entries.push_back(entry);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_summary_test() {
+        assert!(run_summary(&[]).is_none());
+
+        let entry = |summary: &str, code: Option<&str>| LedgerEntry {
+            summary: summary.to_string(),
+            code: code.map(String::from),
+            time: SystemTime::now(),
+        };
+        let recap = run_summary(&[entry("Failed to sync", None), entry("Bad row", Some("E1"))]);
+        let recap = recap.unwrap();
+        assert_eq!(recap.summary(), "2 errors occurred during this run");
+        assert_eq!(
+            recap.reasons().unwrap(),
+            vec!["Failed to sync", "[E1] Bad row"]
+        );
+    }
+}
@@ -17,20 +17,178 @@
 
 // Standard Library Dependencies
 use core::fmt::{self, Debug, Display};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::convert::{Infallible, TryFrom};
 use std::error::Error;
+use std::iter::FromIterator;
+use std::ops::Range;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(feature = "timestamp")]
+use std::time::Instant;
+
+// Crate-generated text, and the optional catalog to translate it
+#[cfg(feature = "i18n")]
+pub mod i18n;
+mod messages;
+
+// Everything needed to get going, in one import
+pub mod prelude;
+
+// Global configuration
+mod config;
+pub use config::{configure, Config};
+
+// Color palettes
+mod theme;
+pub use theme::{current_theme, set_theme, Theme};
+
+// Where errors are printed
+mod output;
+pub use output::{
+    default_stream, output_style, page_long_output, set_default_stream, set_output_style,
+    OutputStyle, Stream,
+};
+
+// Iterating over the lines of an error
+mod lines;
+pub use lines::UserFacingErrorLines;
+
+// Pseudo-errors for expected early exits
+mod graceful;
+pub use graceful::GracefulExit;
+
+// Describing errors without printing them
+mod describe;
+pub use describe::Description;
+
+// Converting any error with a method call
+mod extension;
+pub use extension::IntoUserFacing;
+
+// Sections of errors on their own
+mod sections;
+pub use sections::{ChainDisplay, HelptextDisplay, ReasonsDisplay, SummaryDisplay};
+
+// Machine readable output
+mod hex;
+mod json;
+
+// Terminal capabilities
+mod terminal;
+pub use terminal::{ascii_only, set_ascii_only, set_max_width, set_width, terminal_width};
+
+// Word wrapping
+mod wrap;
+
+// Keyword highlighting
+mod highlight;
+
+// Boxes around errors
+mod frame;
+
+// Lines of source files and values with the offending span underlined
+mod snippet;
+
+// Suggested fixes
+mod suggestion;
+
+// Environment snapshots for bug reports
+mod environment;
+
+// The command line the program was run with, for bug reports
+mod invocation;
+pub use invocation::{capture_invocation, disable_invocation, set_invocation};
+
+// Timestamps in front of errors
+mod timestamp;
+
+// Detailed reports of the errors that end the program
+mod crash_report;
+pub use crash_report::enable_crash_reports;
+
+// Links for reporting the errors that end the program
+mod bug_report;
+pub use bug_report::bug_report_url;
+
+// Mirroring the errors that end the program into an error tracker
+mod report_hook;
+#[cfg(feature = "sentry")]
+pub use report_hook::sentry_report_hook;
+pub use report_hook::{clear_report_hook, set_report_hook, ReportHook};
+
+// A machine-readable copy of each printed error
+mod machine_output;
+pub use machine_output::{disable_machine_output, set_machine_output, MachineOutput};
+
+// Hiding secrets when rendering
+mod redact;
+pub use redact::{redact_values, set_redactor};
+
+// Ambient context for the errors created within a scope
+mod scope;
+pub use scope::{
+    context_scope, err_context, wrap_pending, AttachPending, ContextGuard, PendingGuard,
+};
+
+// Recapping the errors printed during a run
+mod ledger;
+pub use ledger::{
+    enable_error_ledger, print_run_summary, set_error_ledger_capacity, take_error_ledger,
+    LedgerEntry,
+};
+
+// Printing panics as errors
+mod panic;
+pub use panic::install_panic_hook;
+
+// Conventional exit codes
+mod sysexits;
+pub use sysexits::SysexitCode;
+
+// Desktop notifications
+#[cfg(feature = "desktop-notifications")]
+mod desktop;
+
+// Helpers for testing how errors are rendered
+#[cfg(any(test, feature = "testing"))]
+mod testing;
+#[cfg(feature = "testing")]
+pub use testing::assert_renders_eq;
+
+// Procedural macros
+#[cfg(feature = "derive")]
+pub use user_error_derive::user_error;
+
+// Conversions from the standard library's errors about invalid text
+mod encoding_errors;
+
+// Ready-made errors for common network failures
+mod network_errors;
+
+// Conversions from the errors of optional dependencies
+#[cfg(feature = "notify")]
+mod notify_errors;
+#[cfg(feature = "regex")]
+mod regex_errors;
+#[cfg(feature = "semver")]
+mod semver_errors;
+#[cfg(feature = "tokio")]
+mod tokio_errors;
 
 /*************
  * CONSTANTS *
  *************/
 
-// 'Error:' with a red background and white, bold, text
-const SUMMARY_PREFIX: &str = "\u{001b}[97;41;22mError:\u{001b}[91;49;1m ";
-// ' - ' bullet point in yellow and text in bold white
-const REASON_PREFIX: &str = "\u{001b}[93;49;1m - \u{001b}[97;49;1m";
-// Muted white help text
-const HELPTEXT_PREFIX: &str = "\u{001b}[37;49;2m";
-// ASCII Reset formatting escape code
-const RESET: &str = "\u{001b}[0m";
+// Bullet point in front of each reason
+const BULLET: &str = " - ";
+// Label and separators of the single line compact format
+const COMPACT_LABEL: &str = "Error: ";
+const COMPACT_SECTION_SEPARATOR: &str = " | ";
+const COMPACT_REASON_SEPARATOR: &str = "; ";
 
 // Helper function to keep things DRY
 // Takes a dyn Error.source() and returns a Vec of Strings representing all the
@@ -56,37 +214,338 @@ fn error_sources(mut source: Option<&(dyn Error + 'static)>) -> Option<Vec<Strin
 
 // Helper Functions
 
-/// Convenience function that converts the summary into pretty String.
-fn pretty_summary(summary: &str) -> String {
-    [SUMMARY_PREFIX, summary, RESET].concat()
+/// Convenience function that converts the summary, and the error code (if
+/// any), into pretty String.
+fn pretty_summary(summary: &str, code: Option<&str>) -> String {
+    pretty_summary_stamped(summary, code, None)
+}
+
+/// Converts the summary and the error code (if any) into a pretty String,
+/// with a muted timestamp (if any) in front of the label.
+fn pretty_summary_stamped(summary: &str, code: Option<&str>, timestamp: Option<&str>) -> String {
+    let theme = theme::active_theme();
+    let label = messages::message(messages::ERROR_LABEL);
+    let icon = theme.icon_prefix(&theme.error_icon);
+    let stamp = timestamp
+        .map(|timestamp| [&theme.helptext, "[", timestamp, "]", &theme.reset, " "].concat())
+        .unwrap_or_default();
+    let stamp_width = timestamp.map_or(0, |timestamp| wrap::display_width(timestamp) + 3);
+    let taken = stamp_width + wrap::display_width(&icon) + wrap::display_width(&label) + 1;
+    let summary = redact::redact(summary);
+    let summary = match code {
+        Some(code) => format!("[{}] {}", code, summary),
+        None => summary.into_owned(),
+    };
+    let summary = wrap::wrap_hanging(&summary, terminal::wrap_width(), taken);
+    let summary = [
+        &*stamp,
+        &*icon,
+        &theme.label,
+        &*label,
+        &theme.summary,
+        " ",
+        &summary,
+        &theme.reset,
+    ]
+    .concat();
+    terminal::ascii_safe(summary)
 }
 
 /// Convenience function that converts the reasons into pretty String.
 fn pretty_reasons(reasons: Reasons) -> Option<String> {
+    let reasons: Option<Vec<Reason>> =
+        reasons.map(|reasons| reasons.into_iter().map(Reason::from).collect());
+    pretty_reason_list(reasons.as_deref(), &[])
+}
+
+/// Convenience function that converts a list of (possibly indented) reasons
+/// into pretty String, highlighting any of the keywords in them.
+fn pretty_reason_list(reasons: Option<&[Reason]>, keywords: &[String]) -> Option<String> {
     /* Print list of Reasons (if any) */
     if let Some(reasons) = reasons {
+        let theme = theme::active_theme();
+        let width = terminal::wrap_width();
         /* Vector to store the intermediate bullet point strings */
-        let mut reason_strings = Vec::with_capacity(reasons.len());
-        for reason in reasons {
-            let bullet_point = [REASON_PREFIX, &reason].concat();
+        let shown = shown_reasons(reasons.len());
+        let mut reason_strings = Vec::with_capacity(shown + 1);
+        for reason in &reasons[..shown] {
+            /* Indentation goes before the colored bullet so it stays uncolored */
+            let indent = " ".repeat(reason.level * 2);
+            let text = reason.text();
+            let text = redact::redact(&text);
+            let text = highlight::highlight(&text, keywords, &theme.highlight, &theme.reason);
+            let text = wrap::wrap_hanging(&text, width, indent.len() + BULLET.len());
+            let mut bullet_point = [&*indent, &theme.bullet, BULLET, &theme.reason, &text].concat();
+            /* Help for the reason alone goes below it, lined up with its text */
+            if let Some(help) = &reason.help {
+                let taken = indent.len() + BULLET.len();
+                let help = wrap::wrap_hanging(&redact::redact(help), width, taken);
+                let help = [
+                    "\n",
+                    &" ".repeat(taken),
+                    &theme.helptext,
+                    &help,
+                    &theme.reset,
+                ]
+                .concat();
+                bullet_point.push_str(&help);
+            }
             reason_strings.push(bullet_point);
         }
+        /* Count the reasons that were left out */
+        if shown < reasons.len() {
+            reason_strings.push(pretty_more_reasons(reasons.len() - shown));
+        }
         /* Join the buller points with a newline, append a RESET ASCII escape code to the end */
-        Some([&reason_strings.join("\n"), RESET].concat())
+        let reasons = [&reason_strings.join("\n"), &*theme.reset].concat();
+        Some(terminal::ascii_safe(reasons))
     } else {
         None
     }
 }
 
-/// Convenience function that converts the help text into pretty String.
-fn pretty_helptext(helptext: Helptext) -> Option<String> {
-    if let Some(helptext) = helptext {
-        Some([HELPTEXT_PREFIX, &helptext, RESET].concat())
+/// Converts the reasons into a pretty String of numbered bullet points,
+/// starting from `start`, instead of dashes. Indices are zero padded to the
+/// width of the largest one so that the reasons line up, e.g. `01.` through
+/// `12.`.
+/// # Example
+/// ```
+/// use user_error::pretty_reasons_indexed;
+/// let reasons = vec!["Database could not be parsed".to_string()];
+/// let numbered = pretty_reasons_indexed(Some(reasons), 3).unwrap();
+/// assert!(numbered.contains("3. "));
+/// ```
+pub fn pretty_reasons_indexed(reasons: Reasons, start: usize) -> Option<String> {
+    /* Print list of Reasons (if any) */
+    if let Some(reasons) = reasons {
+        let theme = theme::active_theme();
+        let wrap_width = terminal::wrap_width();
+        /* Every index is padded to the width of the last one */
+        let last = start + reasons.len().saturating_sub(1);
+        let width = last.to_string().len();
+        /* Vector to store the intermediate bullet point strings */
+        let mut reason_strings = Vec::with_capacity(reasons.len());
+        for (index, reason) in (start..).zip(reasons) {
+            let number = format!(" {:0width$}. ", index, width = width);
+            let reason = wrap::wrap_hanging(&redact::redact(&reason), wrap_width, number.len());
+            let bullet_point = [&theme.bullet, &*number, &theme.reason, &reason].concat();
+            reason_strings.push(bullet_point);
+        }
+        /* Join the bullet points with a newline, append a RESET ASCII escape code to the end */
+        let reasons = [&reason_strings.join("\n"), &*theme.reset].concat();
+        Some(terminal::ascii_safe(reasons))
     } else {
         None
     }
 }
 
+/// Convenience function that converts the help text into pretty String.
+fn pretty_helptext(helptext: Helptext, keywords: &[String]) -> Option<String> {
+    let theme = theme::active_theme();
+    let icon = theme.icon_prefix(&theme.help_icon);
+    let taken = wrap::display_width(&icon);
+    helptext.map(|helptext| {
+        let helptext = redact::redact(&helptext);
+        let helptext = highlight::highlight(&helptext, keywords, &theme.highlight, &theme.helptext);
+        let helptext = wrap::wrap_hanging(&helptext, terminal::wrap_width(), taken);
+        terminal::ascii_safe([&*icon, &theme.helptext, &*helptext, &theme.reset].concat())
+    })
+}
+
+/// Convenience function that converts the link to the documentation of an
+/// error into a pretty String, in the style of help text. The link is a
+/// hyperlink when the error is styled.
+fn pretty_doc_url(url: Option<&str>) -> Option<String> {
+    let theme = theme::active_theme();
+    url.map(|url| {
+        let link = if theme.reset.is_empty() {
+            url.to_string()
+        } else {
+            terminal::hyperlink(url, url)
+        };
+        let line = messages::message(messages::DOC_URL).replace(messages::URL_PLACEHOLDER, &link);
+        terminal::ascii_safe([&*theme.helptext, &*line, &*theme.reset].concat())
+    })
+}
+
+/// Convenience function that converts the footer into pretty String.
+fn pretty_footer(footer: Option<String>) -> Option<String> {
+    let theme = theme::active_theme();
+    footer.map(|footer| {
+        let footer = wrap::wrap_hanging(&redact::redact(&footer), terminal::wrap_width(), 0);
+        terminal::ascii_safe([&theme.footer, &*footer, &theme.reset].concat())
+    })
+}
+
+/// Convenience function that adds the age of an error, muted, to the end of
+/// the first line of its printed text.
+fn with_age(printed: &str, age: Duration) -> String {
+    let theme = theme::active_theme();
+    let seconds = format!("{:.1}", age.as_secs_f64());
+    let age =
+        messages::message(messages::ERROR_AGE).replace(messages::SECONDS_PLACEHOLDER, &seconds);
+    let note = [" ", &theme.helptext, &*age, &theme.reset].concat();
+    let end = printed.find('\n').unwrap_or(printed.len());
+    [&printed[..end], &note, &printed[end..]].concat()
+}
+
+/// Convenience function that renders the command line the program was run
+/// with, if it is captured and printing is verbose.
+fn pretty_invocation() -> Option<String> {
+    if !config::read(|config| config.verbose) {
+        return None;
+    }
+    let rendered = invocation::render(&invocation::current()?, &theme::active_theme());
+    Some(terminal::ascii_safe(rendered))
+}
+
+/// Convenience function that joins two pretty sections into one, the second
+/// below the first.
+fn join_sections(first: Option<String>, second: Option<String>) -> Option<String> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some([first, second].join("\n")),
+        (first, second) => first.or(second),
+    }
+}
+
+/// Convenience function that joins the pretty sections into the full error,
+/// each section on its own line(s). An error with nothing but a summary has
+/// no trailing newline, so that it can be followed on the same line.
+fn pretty_error(summary: String, reasons: Option<String>, helptext: Option<String>) -> String {
+    // Love this - thanks Rust!
+    match (summary, reasons, helptext) {
+        (summary, None, None) => summary,
+        (summary, Some(reasons), None) => format!("{}\n{}\n", summary, reasons),
+        (summary, None, Some(helptext)) => format!("{}\n{}\n", summary, helptext),
+        (summary, Some(reasons), Some(helptext)) => {
+            format!("{}\n{}\n{}\n", summary, reasons, helptext)
+        }
+    }
+}
+
+/// Appends a pretty section (if any), e.g. the footer, to the full error
+/// after everything else.
+fn append_section(error: String, section: Option<String>) -> String {
+    match section {
+        Some(section) if error.ends_with('\n') => format!("{}{}\n", error, section),
+        Some(section) => format!("{}\n{}\n", error, section),
+        None => error,
+    }
+}
+
+/// How many of the reasons are printed, given the maximum number of reasons
+/// that was set.
+fn shown_reasons(count: usize) -> usize {
+    config::read(|config| config.max_reasons).map_or(count, |max| max.min(count))
+}
+
+/// Convenience function that converts the number of reasons left out into a
+/// pretty notice, lined up with the reasons.
+fn pretty_more_reasons(hidden: usize) -> String {
+    let theme = theme::active_theme();
+    let more = messages::plural(messages::MORE_REASONS, hidden);
+    let notice = [
+        &*" ".repeat(BULLET.len()),
+        &theme.helptext,
+        &*more,
+        &theme.reset,
+    ]
+    .concat();
+    terminal::ascii_safe(notice)
+}
+
+/// Convenience function that converts the number of parts of an error that
+/// were left out into a pretty notice of how to see them, if any were.
+fn pretty_hidden_details(hidden: usize) -> Option<String> {
+    if hidden == 0 {
+        return None;
+    }
+    let theme = theme::active_theme();
+    let command = config::read(Config::verbose_command);
+    let notice = messages::plural(messages::HIDDEN_DETAILS, hidden)
+        .replace(messages::COMMAND_PLACEHOLDER, &command);
+    let notice = wrap::wrap_hanging(&notice, terminal::wrap_width(), 0);
+    Some(terminal::ascii_safe(
+        [&theme.footer, &*notice, &theme.reset].concat(),
+    ))
+}
+
+/// Convenience function that renders the notice that a crash report was
+/// written, in the style of help text.
+fn pretty_crash_report(path: &Path) -> String {
+    let theme = theme::active_theme();
+    let notice = messages::message(messages::CRASH_REPORT)
+        .replace(messages::PATH_PLACEHOLDER, &path.display().to_string());
+    let notice = wrap::wrap_hanging(&notice, terminal::wrap_width(), 0);
+    terminal::ascii_safe([&theme.helptext, &*notice, &theme.reset].concat())
+}
+
+/// Convenience function that renders the link for reporting the error, if
+/// one is configured, in the style of the footer. The link is a hyperlink
+/// when the error is styled.
+fn pretty_bug_report<E: UFE + ?Sized>(error: &E) -> Option<String> {
+    let (template, max_bytes) =
+        config::read(|config| (config.bug_report_url.clone(), config.bug_report_max_bytes));
+    let url = bug_report::fill(
+        &template?,
+        &error.summary(),
+        &error.render_plain(),
+        max_bytes,
+    );
+    let theme = theme::active_theme();
+    let link = if theme.reset.is_empty() {
+        url.clone()
+    } else {
+        terminal::hyperlink(&url, &url)
+    };
+    let line = messages::message(messages::BUG_REPORT).replace(messages::URL_PLACEHOLDER, &link);
+    Some(terminal::ascii_safe(
+        [&theme.footer, &*line, &theme.reset].concat(),
+    ))
+}
+
+/// Convenience function that converts the error into the JSON value that
+/// to_json() and to_json_pretty() encode, with secrets redacted.
+fn json_value<E: UFE + ?Sized>(error: &E) -> json::Value {
+    let text = |text: String| json::Value::String(redact::redact(&text).into_owned());
+    let reasons = error.reasons().unwrap_or_default().into_iter().map(text);
+    let mut fields = vec![
+        ("summary", text(error.summary())),
+        ("reasons", json::Value::Array(reasons.collect())),
+        ("helptext", error.helptext().map_or(json::Value::Null, text)),
+    ];
+    if let Some(footer) = error.footer() {
+        fields.push(("footer", text(footer)));
+    }
+    if let Some(invocation) = invocation::current() {
+        fields.push(("invocation", json::Value::String(invocation)));
+    }
+    let fields = fields
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value));
+    json::Value::Object(fields.collect())
+}
+
+/// Convenience function that renders the error as print() prints it, with
+/// the notice (if any) after everything else.
+fn printed_string<E: UFE + ?Sized>(error: &E, notice: Option<String>) -> String {
+    let reasons = error.reasons().map_or(0, |reasons| reasons.len());
+    let (pretty, hidden) = if config::read(|config| config.quiet) {
+        let summary = pretty_summary(&error.summary(), error.code().as_deref());
+        let helptext = usize::from(error.helptext().is_some());
+        let footer = usize::from(error.footer().is_some());
+        (
+            pretty_error(summary, None, None),
+            reasons + helptext + footer,
+        )
+    } else {
+        (error.to_pretty_string(), reasons - shown_reasons(reasons))
+    };
+    let pretty = append_section(pretty, pretty_hidden_details(hidden));
+    let pretty = append_section(pretty, notice);
+    output::styled(error.stream(), &pretty, || error.to_json())
+}
+
 /// You can implement UFE on your error types pretty print them. The default
 /// implementation will print Error: <your error .to_string()> followed by a list
 /// of reasons that are any errors returned by .source(). You should only
@@ -114,51 +573,400 @@ pub trait UFE: Error {
     }
 
     /// Returns help text that is listed below the reasons in a muted fashion.
-    /// Useful for additional details, or suggested next steps.
+    /// Useful for additional details, or suggested next steps. By default,
+    /// there is none; see UFEConsts for help text that never changes.
     fn helptext(&self) -> Option<String> {
         None
     }
 
+    /// Returns a code identifying the error, which is printed in brackets in
+    /// front of the summary. By default, there is none; see UFEConsts for a
+    /// code that never changes.
+    fn code(&self) -> Option<String> {
+        None
+    }
+
+    /// Returns a footer that is printed after everything else in a very
+    /// dim fashion, e.g. where to report bugs. By default, there is none.
+    fn footer(&self) -> Option<String> {
+        None
+    }
+
+    /// Returns a link to documentation about the error, printed below the
+    /// help text as "See: <url>". By default, there is none.
+    fn doc_url(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the code the program exits with after print_and_exit(). By
+    /// default, this is 1.
+    fn exit_code(&self) -> i32 {
+        1
+    }
+
+    /// Returns how long ago the error was created, if that is known. By
+    /// default, it isn't.
+    fn age(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Returns the stream the error is printed to. By default, this is the
+    /// one set with set_default_stream(), which is stderr unless changed.
+    fn stream(&self) -> Stream {
+        default_stream()
+    }
+
     /**********
      * USE ME *
      **********/
 
-    /// Prints the formatted error.
+    /// Prints the formatted error, records it in the error ledger if that is
+    /// enabled, and writes a copy to the machine output if one is set (see
+    /// [`set_machine_output()`](crate::set_machine_output)). If paging was turned on with
+    /// page_long_output(), an error longer than the threshold is shown
+    /// through the user's pager instead, when printed to a terminal.
     /// # Example
     /// ```
-    /// use user_error::{UserFacingError, UFE};
+    /// use user_error::prelude::*;
     /// UserFacingError::new("File failed to open")
     ///         .reason("File not found")
     ///         .help("Try: touch file.txt")
     ///         .print();
     /// ```
     fn print(&self) {
-        /* Print Summary */
-        eprintln!("{}", pretty_summary(&self.summary()));
+        ledger::record(self);
+        machine_output::write(self);
+        self.stream().print_paged(&self.to_printed_string());
+    }
+
+    /// Prints the formatted error like print(), with how long ago it was
+    /// created after the summary, e.g. "(occurred 2.3s ago)", for errors
+    /// that are printed some time after they happened. Errors whose age
+    /// isn't known are printed as they are.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// UserFacingError::new("File failed to open").print_with_age();
+    /// ```
+    fn print_with_age(&self) {
+        ledger::record(self);
+        machine_output::write(self);
+        let printed = self.to_printed_string();
+        let printed = match self.age() {
+            Some(age) => with_age(&printed, age),
+            None => printed,
+        };
+        self.stream().print_paged(&printed);
+    }
+
+    /// Prints the formatted error to standard error in one piece, through a
+    /// buffered writer shared by every error printed this way. Errors that
+    /// several threads print at once don't interleave, as they can with
+    /// print(). The error is never paged, and is recorded in the error ledger
+    /// if that is enabled.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// UserFacingError::new("File failed to open").print_to_stderr_buffered();
+    /// ```
+    fn print_to_stderr_buffered(&self) {
+        ledger::record(self);
+        machine_output::write(self);
+        output::print_buffered(&self.to_printed_string());
+    }
+
+    /// Returns the error exactly as print() prints it: in the configured
+    /// output style, and only the summary if printing is quiet. If any part
+    /// of the error was left out, a notice of how to see it follows.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let printed = UserFacingError::new("File failed to open")
+    ///         .reason("File not found")
+    ///         .to_printed_string();
+    /// ```
+    fn to_printed_string(&self) -> String {
+        printed_string(self, None)
+    }
+
+    /// Returns the formatted error, with colors.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let pretty = UserFacingError::new("File failed to open")
+    ///         .reason("File not found")
+    ///         .to_pretty_string();
+    /// ```
+    fn to_pretty_string(&self) -> String {
+        let summary = pretty_summary(&self.summary(), self.code().as_deref());
+        let reasons = pretty_reasons(self.reasons());
+        let helptext = pretty_helptext(self.helptext(), &[]);
+        let helptext = join_sections(helptext, pretty_doc_url(self.doc_url()));
+        let footer = pretty_footer(self.footer());
+
+        append_section(pretty_error(summary, reasons, helptext), footer)
+    }
+
+    /// Returns the formatted error without any colors or other escape codes,
+    /// whatever the output style, theme and terminal: the summary, then each
+    /// reason bulleted with ' - ', then the help text, each on its own
+    /// line(s). Ends with a single newline, unless there is nothing but the
+    /// summary. Useful for log files and emails.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let plain = UserFacingError::new("File failed to open")
+    ///         .reason("File not found")
+    ///         .render_plain();
+    /// assert_eq!(plain, "Error: File failed to open\n - File not found\n");
+    /// ```
+    fn render_plain(&self) -> String {
+        terminal::strip_ansi(&self.to_pretty_string())
+    }
+
+    /// Returns a description of the error that is only rendered when it is
+    /// formatted: displayed with colors, or debug formatted without. Useful
+    /// for handing the error to a logger.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let err = UserFacingError::new("File failed to open");
+    /// eprintln!("{}", err.describe());
+    /// ```
+    fn describe(&self) -> Description<'_, Self>
+    where
+        Self: Sized,
+    {
+        Description::new(self)
+    }
+
+    /// Returns the formatted summary on its own, rendered when it is
+    /// formatted. Useful for embedding it in a larger message.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let err = UserFacingError::new("File failed to open");
+    /// let message = format!("{} (while loading the config)", err.summary_as_display());
+    /// ```
+    fn summary_as_display(&self) -> SummaryDisplay<'_, Self>
+    where
+        Self: Sized,
+    {
+        SummaryDisplay::new(self)
+    }
+
+    /// Returns the formatted reasons on their own, rendered when they are
+    /// formatted. Useful for embedding them in a larger message.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let err = UserFacingError::new("File failed to open").reason("File not found");
+    /// let message = format!("Tried config.toml:\n{}", err.reasons_as_display());
+    /// ```
+    fn reasons_as_display(&self) -> ReasonsDisplay<'_, Self>
+    where
+        Self: Sized,
+    {
+        ReasonsDisplay::new(self)
+    }
+
+    /// Returns the formatted help text on its own, rendered when it is
+    /// formatted. Useful for embedding it in a larger message.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let err = UserFacingError::new("File failed to open").help("Try: touch file.txt");
+    /// let message = format!("{}\nSee the manual for more.", err.helptext_as_display());
+    /// ```
+    fn helptext_as_display(&self) -> HelptextDisplay<'_, Self>
+    where
+        Self: Sized,
+    {
+        HelptextDisplay::new(self)
+    }
+
+    /// Returns the summary followed by every error in the source chain,
+    /// without any formatting, rendered when it is formatted. Useful for
+    /// logging the full context of an error.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let err = UserFacingError::new("File failed to open");
+    /// eprintln!("{}", err.chain_display().multiline());
+    /// ```
+    fn chain_display(&self) -> ChainDisplay<'_, Self>
+    where
+        Self: Sized,
+    {
+        ChainDisplay::new(self)
+    }
+
+    /// Returns the error as a single line of JSON, without any formatting:
+    /// `{"summary":"...","reasons":["...", ...],"helptext":"..."}`
+    /// The list of reasons is empty, and the help text is null, if there are
+    /// none. The footer is added as `"footer":"..."` only if there is one, and
+    /// the command line the program was run with as `"invocation":"..."` if
+    /// it is captured (see [`capture_invocation()`](crate::capture_invocation)).
+    /// Secrets are redacted, as configured with
+    /// [`redact_values()`](crate::redact_values).
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let json = UserFacingError::new("File failed to open")
+    ///         .reason("File not found")
+    ///         .to_json();
+    /// assert_eq!(json, r#"{"summary":"File failed to open","reasons":["File not found"],"helptext":null}"#);
+    /// ```
+    fn to_json(&self) -> String {
+        json::compact(&json_value(self))
+    }
+
+    /// Returns the error as JSON like to_json(), but indented over several
+    /// lines so that it is easy to read without another tool.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let json = UserFacingError::new("File failed to open")
+    ///         .reason("File not found")
+    ///         .to_json_pretty();
+    /// let expected = "{\n  \"summary\": \"File failed to open\",\n  \"reasons\": [\n    \
+    ///                 \"File not found\"\n  ],\n  \"helptext\": null\n}";
+    /// assert_eq!(json, expected);
+    /// ```
+    fn to_json_pretty(&self) -> String {
+        json::pretty(&json_value(self))
+    }
+
+    /// Sends the summary of the error, and its first reason, as a desktop
+    /// notification. Useful when the program runs for a long time and the
+    /// user might not be watching the terminal. Fails silently if the
+    /// notification can't be shown.
+    /// # Example
+    /// ```no_run
+    /// use user_error::prelude::*;
+    /// UserFacingError::new("Failed to build project")
+    ///         .reason("Database could not be parsed")
+    ///         .notify();
+    /// ```
+    #[cfg(feature = "desktop-notifications")]
+    fn notify(&self) {
+        desktop::notify(self);
+    }
+
+    /// Prints the formatted error, then sends it as a desktop notification
+    /// (see notify()).
+    /// # Example
+    /// ```no_run
+    /// use user_error::prelude::*;
+    /// UserFacingError::new("Failed to build project")
+    ///         .reason("Database could not be parsed")
+    ///         .print_and_notify();
+    /// ```
+    #[cfg(feature = "desktop-notifications")]
+    fn print_and_notify(&self) {
+        self.print();
+        self.notify();
+    }
 
-        /* Print list of Reasons (if any) */
-        if let Some(reasons) = pretty_reasons(self.reasons()) {
-            eprintln!("{}", reasons);
+    /// Prints the formatted error through the user's pager (the PAGER
+    /// environment variable, or else `less`), and waits for the pager to
+    /// exit. Useful for errors with many reasons. Falls back to print() if the
+    /// pager cannot be started, or stdout is not a terminal.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// UserFacingError::new("Failed to build project")
+    ///         .reason("Database could not be parsed")
+    ///         .print_to_pager();
+    /// ```
+    fn print_to_pager(&self) {
+        let pretty = self.to_pretty_string();
+        if output::page(&pretty).is_err() {
+            self.stream().print(&pretty);
         }
+    }
 
-        /* Print help text (if any) */
-        if let Some(helptext) = pretty_helptext(self.helptext()) {
-            eprintln!("{}", helptext);
+    /// Prints the formatted error, with the reasons numbered from 1 instead
+    /// of bulleted. Useful for step-by-step failure diagnostics.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// UserFacingError::new("Failed to deploy")
+    ///         .reason("Build succeeded")
+    ///         .reason("Upload timed out")
+    ///         .print_indexed_reasons();
+    /// ```
+    fn print_indexed_reasons(&self) {
+        if config::read(|config| config.quiet) {
+            return self.print();
         }
+        let summary = pretty_summary(&self.summary(), self.code().as_deref());
+        let reasons = pretty_reasons_indexed(self.reasons(), 1);
+        let helptext = pretty_helptext(self.helptext(), &[]);
+        let helptext = join_sections(helptext, pretty_doc_url(self.doc_url()));
+        let footer = pretty_footer(self.footer());
+
+        let pretty = append_section(pretty_error(summary, reasons, helptext), footer);
+        let stream = self.stream();
+        stream.print(&output::styled(stream, &pretty, || self.to_json()));
+    }
+
+    /// Returns only the formatted summary line, without the reasons, the help
+    /// text, or a trailing newline. Useful for composing the error into a
+    /// larger report.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let line = UserFacingError::new("File failed to open")
+    ///         .reason("File not found")
+    ///         .summary_only_to_string();
+    /// assert!(!line.ends_with('\n'));
+    /// ```
+    fn summary_only_to_string(&self) -> String {
+        pretty_summary(&self.summary(), self.code().as_deref())
     }
 
     /// Convenience function that pretty prints the error and exits the program.
+    /// If the error is shown through the pager, the program exits once the
+    /// pager does. In the browser (wasm32-unknown-unknown) there is no
+    /// process to exit, so it returns after printing the error.
     /// # Example
     /// ```should_panic
-    /// use user_error::{UserFacingError, UFE};
+    /// use user_error::prelude::*;
     /// UserFacingError::new("File failed to open")
     ///         .reason("File not found")
     ///         .help("Try: touch file.txt")
     ///         .print_and_exit();
     /// ```
     fn print_and_exit(&self) {
+        report_hook::report(self);
+        let crash_report = crash_report::write(self).map(|path| pretty_crash_report(&path));
+        match join_sections(crash_report, pretty_bug_report(self)) {
+            Some(notice) => {
+                ledger::record(self);
+                machine_output::write(self);
+                self.stream()
+                    .print_paged(&printed_string(self, Some(notice)));
+            }
+            None => self.print(),
+        }
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        std::process::exit(self.exit_code())
+    }
+
+    /// Convenience function that prints the error and exits the program
+    /// successfully (with exit code 0). For "errors" that are an expected way
+    /// for the program to end, such as printing its usage. See GracefulExit.
+    /// Like print_and_exit(), it returns after printing in the browser.
+    /// # Example
+    /// ```no_run
+    /// use user_error::{GracefulExit, UFE};
+    /// GracefulExit::new("usage: mytool [FILE]").exit_gracefully();
+    /// ```
+    fn exit_gracefully(&self) {
         self.print();
-        std::process::exit(1)
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        std::process::exit(0)
     }
 
     /// Consumes the UFE and returns a UserFacingError. Useful if you want
@@ -166,7 +974,7 @@ pub trait UFE: Error {
     /// the program.
     /// # Example
     /// ```
-    /// use user_error::{UserFacingError, UFE};
+    /// use user_error::prelude::*;
     /// use std::fmt::{self, Display};
     /// use std::error::Error;
     ///
@@ -193,13 +1001,69 @@ pub trait UFE: Error {
     ///         .print();
     /// }
     /// ```
+    #[allow(clippy::wrong_self_convention)]
     fn into_ufe(&self) -> UserFacingError {
-        UserFacingError {
-            summary: self.summary(),
-            reasons: self.reasons(),
-            helptext: self.helptext(),
-            source: None,
+        let mut error = UserFacingError {
+            reasons: self
+                .reasons()
+                .map(|reasons| reasons.into_iter().map(Reason::from).collect()),
+            helptext: self.helptext().map(Cow::Owned),
+            stream: Some(self.stream()),
+            ..UserFacingError::new(self.summary())
+        };
+        if let Some(code) = self.code() {
+            error = error.with_code(code);
+        }
+        if let Some(url) = self.doc_url() {
+            error = error.with_doc_url(url);
         }
+        match self.footer() {
+            Some(footer) => error.footer(footer),
+            None => error,
+        }
+    }
+}
+
+/// Help text and a code that are the same for every error of a type. Every
+/// type that implements UFEConsts implements UFE, with helptext() and code()
+/// returning the constants, which keeps simple error types to a one-line
+/// impl. Implement UFE directly instead when they depend on the error.
+/// # Example
+/// ```
+/// use user_error::{UFEConsts, UFE};
+/// # use std::fmt::{self, Display};
+/// #[derive(Debug)]
+/// struct NotFound;
+/// # impl Display for NotFound {
+/// #     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+/// #         write!(f, "Not found")
+/// #     }
+/// # }
+/// # impl std::error::Error for NotFound {}
+///
+/// impl UFEConsts for NotFound {
+///     const HELP: Option<&'static str> = Some("Check the spelling");
+///     const CODE: Option<&'static str> = Some("E404");
+/// }
+///
+/// assert_eq!(NotFound.code().unwrap(), "E404");
+/// ```
+pub trait UFEConsts: Error {
+    /// Help text for every error of this type, returned by helptext().
+    const HELP: Option<&'static str> = None;
+
+    /// Code identifying this type of error (e.g. "E0042"), returned by
+    /// code().
+    const CODE: Option<&'static str> = None;
+}
+
+impl<T: UFEConsts> UFE for T {
+    fn helptext(&self) -> Option<String> {
+        T::HELP.map(String::from)
+    }
+
+    fn code(&self) -> Option<String> {
+        T::CODE.map(String::from)
     }
 }
 
@@ -209,75 +1073,390 @@ pub trait UFE: Error {
 type Summary = String;
 type Reasons = Option<Vec<String>>;
 type Helptext = Option<String>;
-type Source = Option<Box<(dyn Error)>>;
+type Source = Option<Box<dyn Error>>;
 
-/// The eponymous struct. You can create a new one from using
-/// user_error::UserFacingError::new() however I recommend you use your own
-/// error types and have them implement UFE instead of using UserFacingError
-/// directly. This is more of an example type, or a way to construct a pretty
-/// messages without implementing your own error type.
+/// A single reason, how many levels deep it is indented when rendered, and
+/// the help text for it alone (if any).
 #[derive(Debug)]
-pub struct UserFacingError {
-    summary: Summary,
-    reasons: Reasons,
-    helptext: Helptext,
-    source: Source,
+struct Reason {
+    text: ReasonText,
+    level: usize,
+    help: Option<Cow<'static, str>>,
 }
 
-/******************
- * IMPLEMENTATION *
- ******************/
+/// The text of a reason: either stored, or computed each time it is rendered
+/// by a function that was added with reason_lazy().
+enum ReasonText {
+    Eager(Cow<'static, str>),
+    Lazy(Box<dyn Fn() -> String>),
+}
 
-// Implement Display so our struct also implements std::error::Error
-impl Display for UserFacingError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let summary = pretty_summary(&self.summary());
-        let reasons = pretty_reasons(self.reasons());
-        let helptext = pretty_helptext(self.helptext());
-
-        // Love this - thanks Rust!
-        match (summary, reasons, helptext) {
-            (summary, None, None) => writeln!(f, "{}", summary),
-            (summary, Some(reasons), None) => writeln!(f, "{}\n{}", summary, reasons),
-            (summary, None, Some(helptext)) => writeln!(f, "{}\n{}", summary, helptext),
-            (summary, Some(reasons), Some(helptext)) => {
-                writeln!(f, "{}\n{}\n{}", summary, reasons, helptext)
-            }
+impl Reason {
+    // The text of the reason, computing it if it is lazy
+    fn text(&self) -> Cow<'static, str> {
+        match &self.text {
+            ReasonText::Eager(text) => text.clone(),
+            ReasonText::Lazy(f) => Cow::Owned(f()),
         }
     }
 }
 
-// Implement std::error::Error
-impl Error for UserFacingError {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
-        match self.source {
-            Some(_) => self.source.as_deref(),
-            None => None,
+impl Debug for ReasonText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReasonText::Eager(text) => f.debug_tuple("Eager").field(text).finish(),
+            ReasonText::Lazy(_) => f.write_str("Lazy(..)"),
         }
     }
 }
 
-// Implement our own trait for our example struct
-// Cloning is not super efficient but this should be the last thing a program
-// does, and it will only do it once so... ¯\_(ツ)_/¯
-impl UFE for UserFacingError {
-    fn summary(&self) -> Summary {
-        self.summary.clone()
+impl From<Cow<'static, str>> for Reason {
+    fn from(text: Cow<'static, str>) -> Reason {
+        Reason {
+            text: ReasonText::Eager(text),
+            level: 0,
+            help: None,
+        }
     }
-    fn reasons(&self) -> Reasons {
-        self.reasons.clone()
+}
+
+impl From<String> for Reason {
+    fn from(text: String) -> Reason {
+        Reason::from(Cow::Owned(text))
+    }
+}
+
+/// Convenience function that strips the indentation levels from a list of
+/// reasons, leaving only their text.
+fn reason_texts(reasons: Option<Vec<Reason>>) -> Reasons {
+    reasons.map(|reasons| {
+        reasons
+            .iter()
+            .map(|reason| reason.text().into_owned())
+            .collect()
+    })
+}
+
+/// The eponymous struct. You can create a new one from using
+/// user_error::UserFacingError::new() however I recommend you use your own
+/// error types and have them implement UFE instead of using UserFacingError
+/// directly. This is more of an example type, or a way to construct a pretty
+/// messages without implementing your own error type.
+// Text is copied, unless it is a string literal added with new_static(),
+// reason_static() or help_static() (and isn't modified later)
+#[derive(Debug)]
+pub struct UserFacingError {
+    summary: Cow<'static, str>,
+    reasons: Option<Vec<Reason>>,
+    helptext: Option<Cow<'static, str>>,
+    source: Source,
+    stream: Option<Stream>,
+    decorations: Option<Box<Decorations>>,
+    modified: bool,
+    #[cfg(feature = "timestamp")]
+    created_at: Instant,
+}
+
+/// Everything about how a UserFacingError is rendered beyond its text. Kept
+/// out of line since most errors use none of it, so that a Result carrying a
+/// UserFacingError stays small.
+#[derive(Debug, Default)]
+struct Decorations {
+    code: Option<Cow<'static, str>>,
+    footer: Option<Cow<'static, str>>,
+    highlights: Vec<String>,
+    span: Option<snippet::Span>,
+    snippets: Vec<snippet::Snippet>,
+    suggestions: Vec<suggestion::Suggestion>,
+    command: Option<Cow<'static, str>>,
+    doc_url: Option<&'static str>,
+    environment: Option<Vec<String>>,
+    boxed: bool,
+    hide_reasons: bool,
+    scope: Vec<String>,
+    exit_code: Option<i32>,
+    #[cfg(feature = "timestamp")]
+    clock: Option<timestamp::Clock>,
+}
+
+// The decorations of an error none were added to
+static NO_DECORATIONS: Decorations = Decorations {
+    code: None,
+    footer: None,
+    highlights: Vec::new(),
+    span: None,
+    snippets: Vec::new(),
+    suggestions: Vec::new(),
+    command: None,
+    doc_url: None,
+    environment: None,
+    boxed: false,
+    hide_reasons: false,
+    scope: Vec::new(),
+    exit_code: None,
+    #[cfg(feature = "timestamp")]
+    clock: None,
+};
+
+// The decorations of an error created within context scopes: the labels of
+// the scopes as reasons. None if there are none, as with most errors.
+fn scoped_decorations() -> Option<Box<Decorations>> {
+    let labels = scope::labels();
+    if labels.is_empty() {
+        return None;
+    }
+    Some(Box::new(Decorations {
+        scope: labels,
+        ..Decorations::default()
+    }))
+}
+
+/// How duplicate reasons are found when removing them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupMode {
+    /// Only repeats of the reason right before are removed. Cheap, even for
+    /// very long lists of reasons.
+    Adjacent,
+    /// Every repeat of an earlier reason is removed, keeping the order in
+    /// which reasons first appear
+    Full,
+}
+
+/// How the end of a summary is punctuated when it is normalized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryStyle {
+    /// Without a trailing period, e.g. "Failed to open file"
+    NoPeriod,
+    /// With a trailing period, e.g. "Failed to open file."
+    Period,
+}
+
+/// Where in the list of reasons a new reason is inserted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReasonPosition {
+    /// Before all other reasons
+    First,
+    /// After all other reasons
+    Last,
+}
+
+/******************
+ * IMPLEMENTATION *
+ ******************/
+
+// Implement Display so our struct also implements std::error::Error
+impl Display for UserFacingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let render = || {
+            let summary = pretty_summary_stamped(
+                &self.summary,
+                self.decorations().code.as_deref(),
+                self.timestamp_text().as_deref(),
+            );
+            let highlights = &self.decorations().highlights;
+            let reasons = pretty_reason_list(self.rendered_reasons().as_deref(), highlights);
+            let reasons = join_sections(reasons, self.pretty_context());
+            let helptext = pretty_helptext(self.helptext(), highlights);
+            let helptext = join_sections(helptext, self.pretty_command());
+            let helptext = join_sections(helptext, pretty_doc_url(self.doc_url()));
+            let footer = pretty_footer(UFE::footer(self));
+            let error = append_section(
+                pretty_error(summary, reasons, helptext),
+                join_sections(pretty_invocation(), self.pretty_environment()),
+            );
+            append_section(error, footer)
+        };
+
+        if self.decorations().boxed {
+            let error = frame::narrowed(render);
+            write!(f, "{}", frame::frame(&error, ascii_only()))
+        } else {
+            write!(f, "{}", render())
+        }
+    }
+}
+
+/// Formats the error as its JSON (see [`UFE::to_json()`]) encoded in
+/// hexadecimal, as a short code that users can pass on, e.g. to support. Read
+/// it back with [`UserFacingError::from_hex()`].
+/// # Example
+/// ```
+/// # use user_error::UserFacingError;
+/// let err = UserFacingError::new("File failed to open");
+/// eprintln!("Error code: {:x} - share this with support", err);
+/// ```
+impl fmt::LowerHex for UserFacingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&hex::encode(self.to_json().as_bytes()))
+    }
+}
+
+/// Allows you to build up the summary of a UserFacingError with write!().
+/// Everything written is appended to the summary.
+/// # Example
+/// ```
+/// # use user_error::UserFacingError;
+/// use std::fmt::Write;
+/// let mut err = UserFacingError::new("Failed to parse config");
+/// write!(err, " at line {}", 7).unwrap();
+/// ```
+impl fmt::Write for UserFacingError {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.summary.to_mut().push_str(s);
+        self.modified = true;
+        Ok(())
+    }
+}
+
+/// Adds every string as a reason, in order.
+/// # Example
+/// ```
+/// # use user_error::UserFacingError;
+/// let missing = vec![String::from("main.db not found"), String::from("config.toml not found")];
+/// let mut err = UserFacingError::new("Failed to build project");
+/// err.extend(missing);
+/// ```
+impl Extend<String> for UserFacingError {
+    fn extend<I: IntoIterator<Item = String>>(&mut self, reasons: I) {
+        let mut reasons = reasons.into_iter().map(Reason::from).peekable();
+        /* Extending with nothing leaves an error without reasons as it is */
+        if reasons.peek().is_some() {
+            self.reasons.get_or_insert_with(Vec::new).extend(reasons);
+            self.modified = true;
+        }
+    }
+}
+
+/// Adds every string as a reason, in order.
+impl<'a> Extend<&'a str> for UserFacingError {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, reasons: I) {
+        self.extend(reasons.into_iter().map(String::from));
+    }
+}
+
+/// Adds every (key, value) pair as a reason of the form "key: value", in
+/// order.
+/// # Example
+/// ```
+/// # use user_error::UserFacingError;
+/// let mut err = UserFacingError::new("Failed to connect");
+/// err.extend(vec![(String::from("host"), String::from("example.com"))]);
+/// ```
+impl Extend<(String, String)> for UserFacingError {
+    fn extend<I: IntoIterator<Item = (String, String)>>(&mut self, pairs: I) {
+        self.extend(
+            pairs
+                .into_iter()
+                .map(|(key, value)| [key, value].join(": ")),
+        );
+    }
+}
+
+/// Collects strings into the reasons of a UserFacingError with a generic
+/// summary. Set a better one with with_summary().
+/// # Example
+/// ```
+/// # use user_error::UserFacingError;
+/// let missing = vec!["main.db", "config.toml"];
+/// let err: UserFacingError = missing
+///     .into_iter()
+///     .map(|file| format!("{} not found", file))
+///     .collect();
+/// let err = err.with_summary("Failed to build project");
+/// ```
+impl FromIterator<String> for UserFacingError {
+    fn from_iter<I: IntoIterator<Item = String>>(reasons: I) -> UserFacingError {
+        let mut error = UserFacingError::new(messages::message(messages::DEFAULT_SUMMARY));
+        error.extend(reasons);
+        error
+    }
+}
+
+/// Collects (summary, reason) pairs into a UserFacingError. The summary of
+/// the first pair is used, and the reason of every pair is added in order.
+/// # Example
+/// ```
+/// # use user_error::UserFacingError;
+/// let failures = vec![
+///     (String::from("Failed to build project"), String::from("main.db not found")),
+///     (String::from("Failed to build project"), String::from("config.toml not found")),
+/// ];
+/// let err: UserFacingError = failures.into_iter().collect();
+/// ```
+impl FromIterator<(String, String)> for UserFacingError {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(pairs: I) -> UserFacingError {
+        let mut pairs = pairs.into_iter().peekable();
+        let summary = match pairs.peek() {
+            Some((summary, _)) => summary.clone(),
+            None => messages::message(messages::DEFAULT_SUMMARY),
+        };
+        let mut error = UserFacingError::new(summary);
+        error.extend(pairs.map(|(_, reason)| reason));
+        error
+    }
+}
+
+// Implement std::error::Error
+impl Error for UserFacingError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.source {
+            Some(_) => self.source.as_deref(),
+            None => None,
+        }
+    }
+}
+
+// Implement our own trait for our example struct
+// Cloning is not super efficient but this should be the last thing a program
+// does, and it will only do it once so... ¯\_(ツ)_/¯
+impl UFE for UserFacingError {
+    fn summary(&self) -> Summary {
+        self.summary.to_string()
+    }
+    fn reasons(&self) -> Reasons {
+        reason_texts(self.all_reasons())
     }
     fn helptext(&self) -> Helptext {
-        self.helptext.clone()
+        self.helptext.as_ref().map(|helptext| helptext.to_string())
+    }
+    fn code(&self) -> Option<String> {
+        self.decorations()
+            .code
+            .as_ref()
+            .map(|code| code.to_string())
+    }
+    fn footer(&self) -> Option<String> {
+        self.decorations()
+            .footer
+            .as_ref()
+            .map(|footer| footer.to_string())
+    }
+    fn exit_code(&self) -> i32 {
+        self.decorations().exit_code.unwrap_or(1)
+    }
+    fn doc_url(&self) -> Option<&'static str> {
+        self.decorations().doc_url
+    }
+    #[cfg(feature = "timestamp")]
+    fn age(&self) -> Option<Duration> {
+        Some(self.created_at.elapsed())
+    }
+    fn stream(&self) -> Stream {
+        self.stream.unwrap_or_else(default_stream)
+    }
+    // Rendered via Display so that reason indentation is preserved
+    fn to_pretty_string(&self) -> String {
+        self.to_string()
     }
 }
 
 // Helper function to keep things DRY
-fn get_ufe_struct_members(error: &(dyn Error)) -> (Summary, Reasons) {
+fn get_ufe_struct_members(error: &dyn Error) -> (Summary, Option<Vec<Reason>>) {
     /* Error Display format is the summary */
     let summary = error.to_string();
     /* Form the reasons from the error source chain */
-    let reasons = error_sources(error.source());
+    let reasons = error_sources(error.source())
+        .map(|reasons| reasons.into_iter().map(Reason::from).collect());
     (summary, reasons)
 }
 
@@ -288,29 +1467,111 @@ fn get_ufe_struct_members(error: &(dyn Error)) -> (Summary, Reasons) {
 impl From<std::io::Error> for UserFacingError {
     fn from(error: std::io::Error) -> UserFacingError {
         let (summary, reasons) = get_ufe_struct_members(&error);
+        /* Explain the kinds of error whose message is vague first */
+        let reasons = match reason_from_kind(error.kind()) {
+            Some(reason) => {
+                let mut reasons = reasons.unwrap_or_default();
+                reasons.insert(0, Reason::from(reason));
+                Some(reasons)
+            }
+            None => reasons,
+        };
+        /* The OS error code last, for looking it up */
+        let reasons = match error.raw_os_error() {
+            Some(code) => {
+                let mut reasons = reasons.unwrap_or_default();
+                let reason = messages::message("io-reason-os-error-code")
+                    .replace(messages::CODE_PLACEHOLDER, &code.to_string())
+                    .replace(messages::ERROR_PLACEHOLDER, &error.to_string());
+                reasons.push(Reason::from(reason));
+                Some(reasons)
+            }
+            None => reasons,
+        };
 
         UserFacingError {
-            summary,
             reasons,
-            helptext: None,
+            helptext: help_from_kind(error.kind()).map(Cow::Owned),
             source: Some(Box::new(error)),
+            ..UserFacingError::new(summary)
         }
     }
 }
 
+/// The number of characters of input shown in parse errors.
+const MAX_INPUT_CHARS: usize = 80;
+
+/// Convenience function that cuts text short with "..." if it is longer than
+/// the given number of characters.
+fn truncated(text: &str, max: usize) -> Cow<'_, str> {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => Cow::Owned([&text[..end], "..."].concat()),
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// A sensible next step for the user to take after each common kind of IO
+/// error, if there is one, translated if a catalog is installed.
+fn help_from_kind(kind: std::io::ErrorKind) -> Option<String> {
+    use std::io::ErrorKind::*;
+    let id = match kind {
+        NotFound => "io-help-not-found",
+        PermissionDenied => "io-help-permission-denied",
+        AlreadyExists => "io-help-already-exists",
+        ConnectionRefused => "io-help-connection-refused",
+        ConnectionReset | ConnectionAborted | TimedOut | HostUnreachable | NetworkUnreachable
+        | NetworkDown => "io-help-check-network",
+        AddrInUse => "io-help-addr-in-use",
+        StorageFull | QuotaExceeded => "io-help-storage-full",
+        ReadOnlyFilesystem => "io-help-read-only-filesystem",
+        _ => return None,
+    };
+    Some(messages::message(id))
+}
+
+/// What went wrong, in plain words, for the kinds of IO error whose own
+/// message is too terse to be of much use on its own, translated if a
+/// catalog is installed.
+fn reason_from_kind(kind: std::io::ErrorKind) -> Option<String> {
+    use std::io::ErrorKind::*;
+    let id = match kind {
+        Unsupported => "io-reason-unsupported",
+        OutOfMemory => "io-reason-out-of-memory",
+        StorageFull => "io-reason-storage-full",
+        QuotaExceeded => "io-reason-quota-exceeded",
+        ReadOnlyFilesystem => "io-reason-read-only-filesystem",
+        FileTooLarge => "io-reason-file-too-large",
+        NotADirectory => "io-reason-not-a-directory",
+        IsADirectory => "io-reason-is-a-directory",
+        DirectoryNotEmpty => "io-reason-directory-not-empty",
+        CrossesDevices => "io-reason-crosses-devices",
+        TooManyLinks => "io-reason-too-many-links",
+        ResourceBusy => "io-reason-resource-busy",
+        ExecutableFileBusy => "io-reason-executable-file-busy",
+        Deadlock => "io-reason-deadlock",
+        ArgumentListTooLong => "io-reason-argument-list-too-long",
+        HostUnreachable => "io-reason-host-unreachable",
+        NetworkUnreachable => "io-reason-network-unreachable",
+        NetworkDown => "io-reason-network-down",
+        StaleNetworkFileHandle => "io-reason-stale-network-file-handle",
+        NotSeekable => "io-reason-not-seekable",
+        _ => return None,
+    };
+    Some(messages::message(id))
+}
+
 /// Allows you to create UserFacingErrors From std Errors.
 /// You should really just implement UFE for your error type, but if you wanted
 /// to convert before quitting so you could add help text of something you can
 /// use this.
-impl From<Box<(dyn Error)>> for UserFacingError {
-    fn from(error: Box<(dyn Error)>) -> UserFacingError {
+impl From<Box<dyn Error>> for UserFacingError {
+    fn from(error: Box<dyn Error>) -> UserFacingError {
         let (summary, reasons) = get_ufe_struct_members(error.as_ref());
 
         UserFacingError {
-            summary,
             reasons,
-            helptext: None,
             source: Some(error),
+            ..UserFacingError::new(summary)
         }
     }
 }
@@ -319,19 +1580,114 @@ impl From<Box<(dyn Error)>> for UserFacingError {
 /// You should really just implement UFE for your error type, but if you wanted
 /// to convert before quitting so you could add help text of something you can
 /// use this.
-impl From<&(dyn Error)> for UserFacingError {
-    fn from(error: &(dyn Error)) -> UserFacingError {
+impl From<&dyn Error> for UserFacingError {
+    fn from(error: &dyn Error) -> UserFacingError {
         let (summary, reasons) = get_ufe_struct_members(error);
 
         UserFacingError {
-            summary,
             reasons,
-            helptext: None,
-            source: None,
+            ..UserFacingError::new(summary)
+        }
+    }
+}
+
+/// An error shared through an Arc, so that it can be kept as the source of a
+/// UserFacingError. Displays as, and has the same source as, the shared error.
+#[derive(Debug)]
+struct SharedError(Arc<dyn Error + Send + Sync>);
+
+impl Display for SharedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Error for SharedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
+
+/// Allows you to create UserFacingErrors From errors shared between threads or
+/// tasks. The shared error is kept as the source, so the error it came from
+/// is still reachable through source().
+impl From<Arc<dyn Error + Send + Sync>> for UserFacingError {
+    fn from(error: Arc<dyn Error + Send + Sync>) -> UserFacingError {
+        let (summary, reasons) = get_ufe_struct_members(error.as_ref());
+
+        UserFacingError {
+            reasons,
+            source: Some(Box::new(SharedError(error))),
+            ..UserFacingError::new(summary)
         }
     }
 }
 
+/// Allows generic code that converts errors into UserFacingErrors to be used
+/// with conversions that cannot fail.
+impl From<Infallible> for UserFacingError {
+    fn from(never: Infallible) -> UserFacingError {
+        match never {}
+    }
+}
+
+/// Allows you to quickly assemble a UserFacingError from a summary and a list
+/// of reasons. An empty list means the error has no reasons.
+/// # Example
+/// ```
+/// # use user_error::UserFacingError;
+/// fn sync() -> Result<(), UserFacingError> {
+///     let reply: Result<(), &str> = Err("connection refused");
+///     reply.map_err(|e| ("Failed to sync", vec!["Server unreachable", e]))?;
+///     Ok(())
+/// }
+/// assert!(sync().is_err());
+/// ```
+impl<'a> From<(&'a str, Vec<&'a str>)> for UserFacingError {
+    fn from((summary, reasons): (&'a str, Vec<&'a str>)) -> UserFacingError {
+        UserFacingError::from((summary.to_string(), owned(reasons)))
+    }
+}
+
+/// Allows you to quickly assemble a UserFacingError from a summary and a list
+/// of reasons. An empty list means the error has no reasons.
+impl From<(String, Vec<String>)> for UserFacingError {
+    fn from((summary, reasons): (String, Vec<String>)) -> UserFacingError {
+        UserFacingError::from_parts(summary, reasons, None)
+    }
+}
+
+/// Allows you to quickly assemble a UserFacingError from a summary, a list of
+/// reasons and help text. An empty list means the error has no reasons.
+/// # Example
+/// ```
+/// # use user_error::UserFacingError;
+/// fn sync() -> Result<(), UserFacingError> {
+///     let reply: Result<(), &str> = Err("connection refused");
+///     reply.map_err(|e| ("Failed to sync", vec![e], "Check your network connection"))?;
+///     Ok(())
+/// }
+/// assert!(sync().is_err());
+/// ```
+impl<'a> From<(&'a str, Vec<&'a str>, &'a str)> for UserFacingError {
+    fn from((summary, reasons, helptext): (&'a str, Vec<&'a str>, &'a str)) -> UserFacingError {
+        UserFacingError::from((summary.to_string(), owned(reasons), helptext.to_string()))
+    }
+}
+
+/// Allows you to quickly assemble a UserFacingError from a summary, a list of
+/// reasons and help text. An empty list means the error has no reasons.
+impl From<(String, Vec<String>, String)> for UserFacingError {
+    fn from((summary, reasons, helptext): (String, Vec<String>, String)) -> UserFacingError {
+        UserFacingError::from_parts(summary, reasons, Some(helptext))
+    }
+}
+
+// Helper function to keep things DRY
+fn owned(texts: Vec<&str>) -> Vec<String> {
+    texts.into_iter().map(String::from).collect()
+}
+
 /// Allows you to create UserFacingErrors From std Errors wrapped in a Result
 /// You should really just implement UFE for your error type, but if you wanted
 /// to convert before quitting so you could add help text of something you can
@@ -343,10 +1699,9 @@ impl<T: Debug> From<Result<T, Box<dyn Error>>> for UserFacingError {
         let (summary, reasons) = get_ufe_struct_members(error.as_ref());
 
         UserFacingError {
-            summary,
             reasons,
-            helptext: None,
             source: Some(error),
+            ..UserFacingError::new(summary)
         }
     }
 }
@@ -354,7 +1709,8 @@ impl<T: Debug> From<Result<T, Box<dyn Error>>> for UserFacingError {
 impl UserFacingError {
     /// This is how users create a new User Facing Error. The value passed to
     /// new() will be used as an error summary. Error summaries are displayed
-    /// first, prefixed by 'Error: '.
+    /// first, prefixed by 'Error: '. The summary is copied, even if it is a
+    /// string literal; use new_static() to store a literal without copying.
     /// # Example
     /// ```
     /// # use user_error::UserFacingError;
@@ -362,142 +1718,1836 @@ impl UserFacingError {
     /// ```
     pub fn new<S: Into<String>>(summary: S) -> UserFacingError {
         UserFacingError {
-            summary: summary.into(),
+            summary: Cow::Owned(summary.into()),
             reasons: None,
             helptext: None,
             source: None,
+            stream: None,
+            decorations: scoped_decorations(),
+            modified: false,
+            #[cfg(feature = "timestamp")]
+            created_at: Instant::now(),
         }
     }
 
-    /// Replace the error summary.
+    /// Creates an error like new(), but from a string literal, which is
+    /// stored without being copied.
     /// # Example
     /// ```
     /// # use user_error::UserFacingError;
-    /// let mut err = UserFacingError::new("File failed to open");
-    /// err.update("Failed Task");
+    /// let err = UserFacingError::new_static("File failed to open");
     /// ```
-    pub fn update<S: Into<String>>(&mut self, summary: S) {
-        self.summary = summary.into();
+    pub fn new_static(summary: &'static str) -> UserFacingError {
+        UserFacingError {
+            summary: Cow::Borrowed(summary),
+            ..UserFacingError::new(String::new())
+        }
     }
 
-    /// Replace the error summary and add the previous error summary to the
-    /// list of reasons
+    // A copy of every reason, followed by those added by the context scopes
+    // the error was created in, or None if there are none
+    fn all_reasons(&self) -> Option<Vec<Reason>> {
+        let own = self.reasons.iter().flatten().map(|reason| Reason {
+            text: ReasonText::Eager(reason.text()),
+            level: reason.level,
+            help: reason.help.clone(),
+        });
+        let scope = self.decorations().scope.iter().cloned().map(Reason::from);
+        let reasons: Vec<Reason> = own.chain(scope).collect();
+        Some(reasons).filter(|reasons| !reasons.is_empty())
+    }
+
+    // Every reason, including those added by context scopes, taken out of
+    // the error
+    fn into_all_reasons(self) -> Vec<Reason> {
+        let scope = self.decorations.map(|decorations| decorations.scope);
+        let reasons = self.reasons.into_iter().flatten();
+        reasons
+            .chain(scope.into_iter().flatten().map(Reason::from))
+            .collect()
+    }
+
+    /// Creates an error that intentionally has nothing but a summary, the
+    /// same as [`UserFacingError::new()`] but clearer about the intent. It
+    /// is printed as the single 'Error: ' line, without a trailing newline,
+    /// so that it can be followed by output of your own.
     /// # Example
     /// ```
     /// # use user_error::UserFacingError;
-    /// let mut err = UserFacingError::new("File failed to open");
-    /// err.push("Failed Task");
+    /// let err = UserFacingError::header_only("File failed to open");
     /// ```
-    pub fn push<S: Into<String>>(&mut self, new_summary: S) {
-        // Add the old summary to the list of reasons
-        let old_summary = self.summary();
-        match self.reasons.as_mut() {
-            Some(reasons) => reasons.insert(0, old_summary),
-            None => self.reasons = Some(vec![old_summary]),
-        }
+    pub fn header_only<S: Into<String>>(summary: S) -> UserFacingError {
+        UserFacingError::new(summary)
+    }
 
-        // Update the summary
-        self.summary = new_summary.into();
+    // The decorations of the error, all turned off if none were added
+    fn decorations(&self) -> &Decorations {
+        self.decorations.as_deref().unwrap_or(&NO_DECORATIONS)
     }
 
-    /// Add a reason to the UserFacingError. Reasons are displayed in a
-    /// bulleted list below the summary, in the reverse order they were added.
+    fn decorations_mut(&mut self) -> &mut Decorations {
+        self.decorations.get_or_insert_with(Default::default)
+    }
+
+    /// Shorthand for `UserFacingError::new(summary).help(helptext)`.
     /// # Example
     /// ```
     /// # use user_error::UserFacingError;
-    /// let err = UserFacingError::new("File failed to open")
-    ///                             .reason("File not found")
-    ///                             .reason("Directory cannot be entered");
+    /// let err = UserFacingError::new_with_help("File failed to open", "Try: touch file.txt");
     /// ```
-    pub fn reason<S: Into<String>>(mut self, reason: S) -> UserFacingError {
-        self.reasons = match self.reasons {
-            Some(mut reasons) => {
-                reasons.push(reason.into());
-                Some(reasons)
-            }
-            None => Some(vec![reason.into()]),
-        };
-        self
+    pub fn new_with_help<S, H>(summary: S, helptext: H) -> UserFacingError
+    where
+        S: Into<String>,
+        H: Into<String>,
+    {
+        UserFacingError::new(summary).help(helptext)
     }
 
-    // Return ref to previous?
+    /// Shorthand for `UserFacingError::new(summary).reason(reason)`.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new_with_reason("File failed to open", "File not found");
+    /// ```
+    pub fn new_with_reason<S, R>(summary: S, reason: R) -> UserFacingError
+    where
+        S: Into<String>,
+        R: Into<String>,
+    {
+        UserFacingError::new(summary).reason(reason)
+    }
 
-    /// Clears all reasons from a UserFacingError.
+    /// Shorthand for `UserFacingError::new(summary).reason(reason).help(helptext)`.
     /// # Example
     /// ```
     /// # use user_error::UserFacingError;
-    /// let mut err = UserFacingError::new("File failed to open")
-    ///                             .reason("File not found")
-    ///                             .reason("Directory cannot be entered");
-    /// err.clear_reasons();
+    /// let err = UserFacingError::new_full(
+    ///     "File failed to open",
+    ///     "File not found",
+    ///     "Try: touch file.txt",
+    /// );
     /// ```
-    pub fn clear_reasons(&mut self) {
-        self.reasons = None;
+    pub fn new_full<S, R, H>(summary: S, reason: R, helptext: H) -> UserFacingError
+    where
+        S: Into<String>,
+        R: Into<String>,
+        H: Into<String>,
+    {
+        UserFacingError::new(summary).reason(reason).help(helptext)
     }
 
-    /// Add help text to the error. Help text is displayed last, in a muted
-    /// fashion.
+    /// An error for a required environment variable that is not set, which
+    /// tells the user how to set it.
     /// # Example
     /// ```
     /// # use user_error::UserFacingError;
-    /// let err = UserFacingError::new("File failed to open")
-    ///                             .reason("File not found")
-    ///                             .help("Check if the file exists.");
+    /// let err = UserFacingError::from_env_missing("DATABASE_URL");
     /// ```
-    pub fn help<S: Into<String>>(mut self, helptext: S) -> UserFacingError {
-        self.helptext = Some(helptext.into());
-        self
+    pub fn from_env_missing(var_name: &str) -> UserFacingError {
+        let text = |id| messages::message(id).replace(messages::VAR_PLACEHOLDER, var_name);
+        UserFacingError::new_full(
+            messages::message("env-missing-summary"),
+            text("env-missing-reason"),
+            text("env-missing-help"),
+        )
     }
 
-    /// Clears all the help text from a UserFacingError.
+    /// An error for a file that does not exist.
     /// # Example
     /// ```
     /// # use user_error::UserFacingError;
-    /// let mut err = UserFacingError::new("File failed to open")
-    ///                             .reason("File not found")
-    ///                             .reason("Directory cannot be entered")
-    ///                             .help("Check if the file exists.");
-    /// err.clear_helptext();
+    /// # use std::path::Path;
+    /// let err = UserFacingError::from_file_not_found(Path::new("config.toml"));
     /// ```
-    pub fn clear_helptext(&mut self) {
-        self.helptext = None;
+    pub fn from_file_not_found(path: &Path) -> UserFacingError {
+        UserFacingError::new_full(
+            messages::message("file-not-found-summary"),
+            path.display().to_string(),
+            messages::message("file-not-found-help"),
+        )
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    // Statics to keep the testing DRY/cleaner
-    static S: &'static str = "Test Error";
-    static R: &'static str = "Reason 1";
-    static H: &'static str = "Try Again";
+    /// An error for a file that the user isn't allowed to access, which
+    /// tells them how to get access on their platform.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// # use std::path::Path;
+    /// let err = UserFacingError::from_permission_denied(Path::new("/etc/shadow"));
+    /// ```
+    pub fn from_permission_denied(path: &Path) -> UserFacingError {
+        UserFacingError::new_full(
+            messages::message("permission-denied-summary"),
+            messages::message("permission-denied-reason")
+                .replace(messages::PATH_PLACEHOLDER, &path.display().to_string()),
+            messages::message("permission-denied-help"),
+        )
+    }
 
-    #[test]
-    fn new_test() {
-        eprintln!("{}", UserFacingError::new("Test Error"));
+    /// Creates an error from a message of the form "context: cause", with
+    /// the context as the summary and the cause as its only reason. The
+    /// message is split at the first ": ". A message without one is all
+    /// summary.
+    /// # Example
+    /// ```
+    /// # use user_error::{UserFacingError, UFE};
+    /// let err = UserFacingError::from_colon_split("Failed to open file: permission denied");
+    /// assert_eq!(err.summary(), "Failed to open file");
+    /// ```
+    pub fn from_colon_split(message: &str) -> UserFacingError {
+        match message.split_once(": ") {
+            Some((summary, reason)) => {
+                UserFacingError::new(summary.to_string()).reason(reason.to_string())
+            }
+            None => UserFacingError::new(message.to_string()),
+        }
     }
 
-    #[test]
-    fn summary_test() {
-        let e = UserFacingError::new(S);
-        let expected = [SUMMARY_PREFIX, S, RESET, "\n"].concat();
-        assert_eq!(e.to_string(), String::from(expected));
-        eprintln!("{}", e);
+    /// An error for input that could not be parsed, e.g. a config value or a
+    /// command line argument, saying what was expected instead. Input longer
+    /// than 80 characters is cut short with "...".
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::from_parse_failure("12:60", "HH:MM");
+    /// ```
+    pub fn from_parse_failure(input: &str, expected: &str) -> UserFacingError {
+        let reason = messages::fill(
+            &messages::message("parse-failure-reason"),
+            &[
+                (
+                    messages::INPUT_PLACEHOLDER,
+                    &truncated(input, MAX_INPUT_CHARS),
+                ),
+                (messages::EXPECTED_PLACEHOLDER, expected),
+            ],
+        );
+        UserFacingError::new_full(
+            messages::message("parse-failure-summary"),
+            reason,
+            messages::message("parse-failure-help"),
+        )
     }
 
-    #[test]
-    fn helptext_test() {
-        let e = UserFacingError::new(S).help(H);
-        let expected = format!(
-            "{}{}{}\n{}{}{}\n",
-            SUMMARY_PREFIX, S, RESET, HELPTEXT_PREFIX, H, RESET
+    /// An error for input that could not be parsed, like
+    /// [`from_parse_failure()`](UserFacingError::from_parse_failure), which
+    /// also says where in the input parsing failed: the position counts
+    /// characters from 0, and the input from there on is shown.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::from_parse_failure_at("12:60", 3, "HH:MM");
+    /// ```
+    pub fn from_parse_failure_at(input: &str, position: usize, expected: &str) -> UserFacingError {
+        let rest: String = input.chars().skip(position).collect();
+        let reason = messages::fill(
+            &messages::message("parse-failure-reason-position"),
+            &[
+                (messages::POSITION_PLACEHOLDER, &position.to_string()),
+                (
+                    messages::INPUT_PLACEHOLDER,
+                    &truncated(&rest, MAX_INPUT_CHARS),
+                ),
+            ],
         );
-        assert_eq!(e.to_string(), expected);
-        eprintln!("{}", e);
+        UserFacingError::from_parse_failure(input, expected).reason(reason)
+    }
+
+    /// Highlight every occurrence of the keywords (e.g. a file name, a flag
+    /// or a config key) in the reasons and help text, so that they stand out.
+    /// Where keywords overlap, the one that starts first wins.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to load config")
+    ///                             .reason("Key 'port' in config.toml is not a number")
+    ///                             .highlight(&["config.toml", "'port'"]);
+    /// ```
+    pub fn highlight(mut self, keywords: &[&str]) -> UserFacingError {
+        self.decorations_mut()
+            .highlights
+            .extend(keywords.iter().map(|keyword| keyword.to_string()));
+        self
+    }
+
+    /// Show the line of a source file the error is about, with the span
+    /// starting at `column` (counted in characters from 1) underlined, as in:
+    /// ```text
+    ///  --> config.toml:7:8
+    ///   |
+    /// 7 | port = "abc"
+    ///   |        ^^^^^
+    /// ```
+    /// The line is printed as is, below the reasons. Several snippets are
+    /// shown one after another.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// use std::path::Path;
+    /// let err = UserFacingError::new("Failed to load config")
+    ///                             .reason("'port' is not a number")
+    ///                             .snippet(Path::new("config.toml"), 7, 8, "port = \"abc\"", 5);
+    /// ```
+    pub fn snippet(
+        mut self,
+        path: &Path,
+        line: u32,
+        column: u32,
+        source_line: &str,
+        span_len: usize,
+    ) -> UserFacingError {
+        self.decorations_mut().snippets.push(snippet::Snippet {
+            path: path.to_path_buf(),
+            line,
+            column,
+            source_line: source_line.to_string(),
+            span_len,
+        });
+        self
+    }
+
+    /// Show a value the user typed in (e.g. a command line argument) on its
+    /// own line, with the part of it in the byte range underlined. A range
+    /// that doesn't fit the text is shrunk to fit, and one that starts or
+    /// ends in the middle of a character is widened to include it.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Invalid date")
+    ///                             .reason("There is no month 13")
+    ///                             .highlight_span("2024-13-01", 5..7);
+    /// ```
+    pub fn highlight_span(mut self, text: &str, range: Range<usize>) -> UserFacingError {
+        self.decorations_mut().span = Some(snippet::Span::new(text, range));
+        self
+    }
+
+    /// Suggest a fix, shown as the line before and after it is applied like
+    /// a diff, below the description. Several fixes are shown one after
+    /// another, after any snippets.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to load config")
+    ///                             .reason("'port' is not a number")
+    ///                             .suggest_fix("Use a number", "port = \"abc\"", "port = 8080");
+    /// ```
+    pub fn suggest_fix(mut self, description: &str, before: &str, after: &str) -> UserFacingError {
+        self.decorations_mut()
+            .suggestions
+            .push(suggestion::Suggestion {
+                description: description.to_string(),
+                before: before.to_string(),
+                after: after.to_string(),
+            });
+        self
+    }
+
+    // The highlighted value, the snippets and the suggested fixes, one after
+    // another, if there are any
+    fn pretty_context(&self) -> Option<String> {
+        let theme = theme::active_theme();
+        let decorations = self.decorations();
+        let span = decorations.span.as_ref().map(|span| span.render(&theme));
+        let snippets = snippet::render(&decorations.snippets, &theme);
+        let suggestions = suggestion::render(&decorations.suggestions, &theme);
+        join_sections(join_sections(span, snippets), suggestions).map(terminal::ascii_safe)
+    }
+
+    /// Include a snapshot of the environment for bug reports: the version of
+    /// the program (see [`Config::app_version`]), the operating system and
+    /// architecture, and the values of the environment variables. It is
+    /// printed after the help text, but only in verbose mode (see
+    /// [`Config::verbose`]).
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to open editor")
+    ///                             .include_environment(&["EDITOR", "VISUAL"]);
+    /// ```
+    pub fn include_environment(mut self, keys: &[&str]) -> UserFacingError {
+        self.decorations_mut()
+            .environment
+            .get_or_insert_with(Vec::new)
+            .extend(keys.iter().map(|key| key.to_string()));
+        self
+    }
+
+    // The environment snapshot, if one is included and printing is verbose
+    fn pretty_environment(&self) -> Option<String> {
+        let keys = self.decorations().environment.as_ref()?;
+        let (verbose, version) =
+            config::read(|config| (config.verbose, config.app_version.clone()));
+        if !verbose {
+            return None;
+        }
+        let rendered = environment::render(
+            keys,
+            version.as_deref(),
+            |key| std::env::var_os(key),
+            &theme::active_theme(),
+        );
+        Some(terminal::ascii_safe(rendered))
+    }
+
+    /// Draw a box around the whole error, to make it stand out. Every line is
+    /// padded to the width of the widest one, and the error is wrapped so that
+    /// the box fits in the terminal. Drawn with ASCII characters when
+    /// rendering is ASCII-only.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("File failed to open")
+    ///                             .reason("File not found")
+    ///                             .boxed(true);
+    /// ```
+    pub fn boxed(mut self, yes: bool) -> UserFacingError {
+        self.decorations_mut().boxed = yes;
+        self
+    }
+
+    /// Show the reasons (true, the default), or leave them out when the error
+    /// is rendered (false), e.g. when they are too technical for the users of
+    /// a program. The summary and help text are still shown.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to sync")
+    ///                             .reason("ECONNRESET on socket 7")
+    ///                             .help("Try again in a few minutes")
+    ///                             .show_reasons(false);
+    /// ```
+    pub fn show_reasons(mut self, yes: bool) -> UserFacingError {
+        self.decorations_mut().hide_reasons = !yes;
+        self
+    }
+
+    // The reasons that are rendered, which is none of them if they're hidden
+    fn rendered_reasons(&self) -> Option<Vec<Reason>> {
+        if self.decorations().hide_reasons {
+            None
+        } else {
+            self.all_reasons()
+        }
+    }
+
+    /// Prefix the summary with the current UTC time, as in
+    /// `[2024-01-01T12:00:00Z] Error: ...`, for log-like output. Off by
+    /// default. The time is taken when the error is rendered.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("File failed to open").timestamp(true);
+    /// ```
+    #[cfg(feature = "timestamp")]
+    pub fn timestamp(mut self, yes: bool) -> UserFacingError {
+        self.decorations_mut().clock = if yes {
+            Some(std::time::SystemTime::now)
+        } else {
+            None
+        };
+        self
+    }
+
+    // The time the error is stamped with, if timestamps are turned on
+    #[cfg(feature = "timestamp")]
+    fn timestamp_text(&self) -> Option<String> {
+        self.decorations()
+            .clock
+            .map(|clock| timestamp::rfc3339(clock()))
+    }
+
+    #[cfg(not(feature = "timestamp"))]
+    fn timestamp_text(&self) -> Option<String> {
+        None
+    }
+
+    /// Converts the error of a Result into a UserFacingError, or returns None
+    /// if the Result is Ok.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let result = std::fs::read("does-not-exist.txt");
+    /// if let Some(err) = UserFacingError::from_result(result) {
+    ///     err.print();
+    /// }
+    /// ```
+    pub fn from_result<T, E: Into<UserFacingError>>(
+        result: Result<T, E>,
+    ) -> Option<UserFacingError> {
+        result.err().map(Into::into)
+    }
+
+    /// Creates a UserFacingError with the given summary and the error of a
+    /// Result as its reason, or returns None if the Result is Ok. For errors
+    /// that can't be converted into a UserFacingError.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let result = "forty-two".parse::<u32>();
+    /// let err = UserFacingError::from_result_or(result, "Invalid port").unwrap();
+    /// ```
+    pub fn from_result_or<T, E: Display>(
+        result: Result<T, E>,
+        summary: &str,
+    ) -> Option<UserFacingError> {
+        result
+            .err()
+            .map(|error| UserFacingError::new(summary.to_string()).reason(error.to_string()))
+    }
+
+    /// Replace the error summary.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let mut err = UserFacingError::new("File failed to open");
+    /// err.update("Failed Task");
+    /// ```
+    pub fn update<S: Into<String>>(&mut self, summary: S) {
+        self.summary = Cow::Owned(summary.into());
+        self.modified = true;
+    }
+
+    /// Replace the error summary, builder style.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("File failed to open").with_summary("Failed Task");
+    /// ```
+    pub fn with_summary<S: Into<String>>(mut self, summary: S) -> UserFacingError {
+        self.update(summary);
+        self
+    }
+
+    /// Capitalize the first letter of the summary and remove any trailing
+    /// periods, so that summaries look alike however they were written.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("failed to open file.").normalize_summary();
+    /// ```
+    pub fn normalize_summary(self) -> UserFacingError {
+        self.normalize_summary_with(SummaryStyle::NoPeriod)
+    }
+
+    /// Capitalize the first letter of the summary and punctuate its end as
+    /// the style says. A summary that ends in '!' or '?' is never given a
+    /// period.
+    /// # Example
+    /// ```
+    /// # use user_error::{SummaryStyle, UserFacingError};
+    /// let err = UserFacingError::new("failed to open file")
+    ///                             .normalize_summary_with(SummaryStyle::Period);
+    /// ```
+    pub fn normalize_summary_with(mut self, style: SummaryStyle) -> UserFacingError {
+        let trimmed = self.summary.trim_end().trim_end_matches('.');
+        let mut chars = trimmed.chars();
+        let mut summary: String = match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        };
+        if style == SummaryStyle::Period
+            && !summary.is_empty()
+            && !summary.ends_with(&['!', '?'][..])
+        {
+            summary.push('.');
+        }
+        self.update(summary);
+        self
+    }
+
+    /// Wraps the error with outer context: the summary is replaced with
+    /// `outer_summary` and the previous summary becomes the first reason.
+    /// # Example
+    /// ```
+    /// # use user_error::{UserFacingError, UFE};
+    /// let err = UserFacingError::new("File failed to open").wrap_with("Failed to load config");
+    /// assert_eq!(err.summary(), "Failed to load config");
+    /// assert_eq!(err.reasons().unwrap(), vec!["File failed to open"]);
+    /// ```
+    pub fn wrap_with<S: Into<String>>(mut self, outer_summary: S) -> UserFacingError {
+        self.wrap_in_place(outer_summary);
+        self
+    }
+
+    /// Replace the error summary and add the previous error summary to the
+    /// list of reasons
+    /// # Example
+    /// ```
+    /// # #![allow(deprecated)]
+    /// # use user_error::UserFacingError;
+    /// let mut err = UserFacingError::new("File failed to open");
+    /// err.push("Failed Task");
+    /// ```
+    #[deprecated(note = "use `wrap_with` instead")]
+    pub fn push<S: Into<String>>(&mut self, new_summary: S) {
+        self.wrap_in_place(new_summary);
+    }
+
+    // Replaces the summary, keeping the previous one as the first reason
+    fn wrap_in_place<S: Into<String>>(&mut self, new_summary: S) {
+        // Add the old summary to the list of reasons
+        let old_summary = Reason::from(std::mem::take(&mut self.summary));
+        match self.reasons.as_mut() {
+            Some(reasons) => reasons.insert(0, old_summary),
+            None => self.reasons = Some(vec![old_summary]),
+        }
+
+        // Update the summary
+        self.summary = Cow::Owned(new_summary.into());
+        self.modified = true;
+    }
+
+    /// Add the current error summary to the list of reasons without changing
+    /// the summary. Useful when the error is built up in stages and the new
+    /// summary isn't known yet. The summary becomes the first reason.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let mut err = UserFacingError::new("File not found").repeat_summary_as_reason();
+    /// err.update("Failed to load config");
+    /// ```
+    pub fn repeat_summary_as_reason(self) -> UserFacingError {
+        self.repeat_summary_as_reason_at(ReasonPosition::First)
+    }
+
+    /// Add the current error summary to the list of reasons, at the given
+    /// position, without changing the summary.
+    /// # Example
+    /// ```
+    /// # use user_error::{ReasonPosition, UserFacingError};
+    /// let mut err = UserFacingError::new("File not found")
+    ///                             .reason("Directory cannot be entered")
+    ///                             .repeat_summary_as_reason_at(ReasonPosition::Last);
+    /// err.update("Failed to load config");
+    /// ```
+    pub fn repeat_summary_as_reason_at(mut self, position: ReasonPosition) -> UserFacingError {
+        let summary = Reason::from(self.summary.clone());
+        let reasons = self.reasons.get_or_insert_with(Vec::new);
+        match position {
+            ReasonPosition::First => reasons.insert(0, summary),
+            ReasonPosition::Last => reasons.push(summary),
+        }
+        self.modified = true;
+        self
+    }
+
+    /// Add a reason to the UserFacingError. Reasons are displayed in a
+    /// bulleted list below the summary, in the reverse order they were added.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("File failed to open")
+    ///                             .reason("File not found")
+    ///                             .reason("Directory cannot be entered");
+    /// ```
+    pub fn reason<S: Into<String>>(self, reason: S) -> UserFacingError {
+        self.reason_indented(reason, 0)
+    }
+
+    /// Add a reason like reason(), but from a string literal, which is stored
+    /// without being copied.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new_static("File failed to open")
+    ///                             .reason_static("File not found");
+    /// ```
+    pub fn reason_static(self, reason: &'static str) -> UserFacingError {
+        self.push_reason(Reason::from(Cow::Borrowed(reason)))
+    }
+
+    /// Add a reason to the UserFacingError only if there is one, saving a
+    /// match when whether there is a reason depends on an Option.
+    /// # Example
+    /// ```
+    /// # use user_error::{UserFacingError, UFE};
+    /// let line: Option<String> = None;
+    /// let err = UserFacingError::new("Config file is invalid")
+    ///                             .reason_if_some(line.map(|line| format!("Error on line {}", line)));
+    /// assert!(err.reasons().is_none());
+    /// ```
+    pub fn reason_if_some<S: Into<String>>(self, reason: Option<S>) -> UserFacingError {
+        match reason {
+            Some(reason) => self.reason(reason),
+            None => self,
+        }
+    }
+
+    /// Add a reason to the UserFacingError that is indented `level` steps
+    /// deeper than a regular reason. Each level adds two spaces before the
+    /// bullet point, which is useful for showing a hierarchy of reasons.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to build project")
+    ///                             .reason("Database could not be parsed")
+    ///                             .reason_indented("File \"main.db\" not found", 1);
+    /// ```
+    pub fn reason_indented<S: Into<String>>(self, reason: S, level: usize) -> UserFacingError {
+        self.push_reason(Reason {
+            level,
+            ..Reason::from(reason.into())
+        })
+    }
+
+    /// Add a reason whose text is only computed when the error is rendered,
+    /// by calling the function. Useful when the text is expensive to build,
+    /// e.g. a large data structure formatted for humans, and the error might
+    /// be handled without ever being shown. The function is called each time
+    /// the error is rendered.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let pending = vec!["main.db", "config.toml"];
+    /// let err = UserFacingError::new("Failed to build project")
+    ///                             .reason_lazy(move || format!("Pending: {}", pending.join(", ")));
+    /// ```
+    pub fn reason_lazy<F: Fn() -> String + 'static>(self, f: F) -> UserFacingError {
+        self.push_reason(Reason {
+            text: ReasonText::Lazy(Box::new(f)),
+            level: 0,
+            help: None,
+        })
+    }
+
+    /// Add a reason with help text of its own, e.g. how to fix that one
+    /// problem. The help text is displayed in a muted fashion right below the
+    /// reason, lined up with it. The help text of the whole error is still
+    /// displayed last.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to build project")
+    ///                             .reason_with_help("main.db not found", "Run: mytool init")
+    ///                             .reason_with_help("config.toml is invalid", "Check line 3");
+    /// ```
+    pub fn reason_with_help<R, H>(self, reason: R, help: H) -> UserFacingError
+    where
+        R: Into<String>,
+        H: Into<String>,
+    {
+        self.push_reason(Reason {
+            help: Some(Cow::Owned(help.into())),
+            ..Reason::from(reason.into())
+        })
+    }
+
+    // Adds a reason after the others
+    fn push_reason(mut self, reason: Reason) -> UserFacingError {
+        self.reasons = match self.reasons {
+            Some(mut reasons) => {
+                reasons.push(reason);
+                Some(reasons)
+            }
+            None => Some(vec![reason]),
+        };
+        self.modified = true;
+        self
+    }
+
+    /// Add every reason in a slice, in order.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("File failed to open")
+    ///                             .reason_list(&["File not found", "Directory cannot be entered"]);
+    /// ```
+    pub fn reason_list(mut self, reasons: &[&str]) -> UserFacingError {
+        self.extend(reasons.iter().copied());
+        self
+    }
+
+    /// Add each line of a multi-line string as a separate reason, in order.
+    /// Blank lines are skipped. Useful for the newline separated diagnostics
+    /// of an underlying tool.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let output = "main.db: not found\n\nconfig.toml: not found\n";
+    /// let err = UserFacingError::new("Failed to build project")
+    ///                             .reasons_from_lines(output);
+    /// ```
+    pub fn reasons_from_lines(self, text: &str) -> UserFacingError {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .fold(self, |error, line| error.reason(line.to_string()))
+    }
+
+    /// Prepends the prefix to every reason added so far, e.g. to show which
+    /// subsystem they came from. Each reason keeps its indentation and help
+    /// text, and reasons added with reason_lazy() are still computed only when
+    /// rendered.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to start")
+    ///                             .reason("Missing key \"port\"")
+    ///                             .prefix_reasons("[config] ");
+    /// ```
+    pub fn prefix_reasons(self, prefix: &str) -> UserFacingError {
+        let prefix = prefix.to_string();
+        self.map_reasons(move |text| [prefix.as_str(), text].concat())
+    }
+
+    /// Appends the suffix to every reason added so far. Like
+    /// prefix_reasons(), each reason keeps its indentation, help text and
+    /// laziness.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to start")
+    ///                             .reason("Connection refused")
+    ///                             .suffix_reasons(" (network)");
+    /// ```
+    pub fn suffix_reasons(self, suffix: &str) -> UserFacingError {
+        let suffix = suffix.to_string();
+        self.map_reasons(move |text| [text, suffix.as_str()].concat())
+    }
+
+    // Rewrites the text of each reason. Lazy reasons stay lazy, with the
+    // rewrite applied each time they are computed.
+    fn map_reasons<F: Fn(&str) -> String + Clone + 'static>(mut self, f: F) -> UserFacingError {
+        if let Some(reasons) = self.reasons.as_mut() {
+            for reason in reasons.iter_mut() {
+                let f = f.clone();
+                let text =
+                    std::mem::replace(&mut reason.text, ReasonText::Eager(Cow::Borrowed("")));
+                reason.text = match text {
+                    ReasonText::Eager(text) => ReasonText::Eager(Cow::Owned(f(&text))),
+                    ReasonText::Lazy(g) => ReasonText::Lazy(Box::new(move || f(&g()))),
+                };
+            }
+            self.modified = true;
+        }
+        self
+    }
+
+    // Return ref to previous?
+
+    /// Removes duplicate reasons, found as the mode says. Reasons only count
+    /// as duplicates if they are indented the same.
+    /// # Example
+    /// ```
+    /// # use user_error::{DedupMode, UserFacingError};
+    /// let mut err = UserFacingError::new("Failed to build project")
+    ///                             .reason("main.db not found")
+    ///                             .reason("main.db not found");
+    /// err.dedup_reasons_with(DedupMode::Adjacent);
+    /// ```
+    pub fn dedup_reasons_with(&mut self, mode: DedupMode) {
+        if let Some(reasons) = self.reasons.as_mut() {
+            match mode {
+                DedupMode::Adjacent => {
+                    reasons.dedup_by(|a, b| a.level == b.level && a.text() == b.text())
+                }
+                DedupMode::Full => {
+                    let mut seen = HashSet::new();
+                    reasons.retain(|reason| seen.insert((reason.text(), reason.level)));
+                }
+            }
+            self.modified = true;
+        }
+    }
+
+    /// Clears all reasons from a UserFacingError.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let mut err = UserFacingError::new("File failed to open")
+    ///                             .reason("File not found")
+    ///                             .reason("Directory cannot be entered");
+    /// err.clear_reasons();
+    /// ```
+    pub fn clear_reasons(&mut self) {
+        self.reasons = None;
+        self.modified = true;
+    }
+
+    /// Add help text to the error. Help text is displayed last, in a muted
+    /// fashion.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("File failed to open")
+    ///                             .reason("File not found")
+    ///                             .help("Check if the file exists.");
+    /// ```
+    pub fn help<S: Into<String>>(mut self, helptext: S) -> UserFacingError {
+        self.helptext = Some(Cow::Owned(helptext.into()));
+        self.modified = true;
+        self
+    }
+
+    /// Add help text like help(), but from a string literal, which is stored
+    /// without being copied.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new_static("File failed to open")
+    ///                             .help_static("Check if the file exists.");
+    /// ```
+    pub fn help_static(mut self, helptext: &'static str) -> UserFacingError {
+        self.helptext = Some(Cow::Borrowed(helptext));
+        self.modified = true;
+        self
+    }
+
+    /// Add help text to the error only if there is some. Help text added
+    /// before is kept otherwise.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let fix = std::env::var("MYTOOL_FIX").ok();
+    /// let err = UserFacingError::new("File failed to open").help_if_some(fix);
+    /// ```
+    pub fn help_if_some<S: Into<String>>(self, helptext: Option<S>) -> UserFacingError {
+        match helptext {
+            Some(helptext) => self.help(helptext),
+            None => self,
+        }
+    }
+
+    /// Add help text suggesting a command to run, as in "Try: `cargo clean`".
+    /// The command is highlighted so that it stands out.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to build project")
+    ///                             .with_suggestion_command("cargo clean");
+    /// ```
+    pub fn with_suggestion_command(self, command: &str) -> UserFacingError {
+        self.with_suggestion_commands(&[command])
+    }
+
+    /// Add help text with a command the user can copy and run, e.g. to fix
+    /// the error. The command is printed on its own line below the help text,
+    /// after a `$ ` prompt, styled apart from the text around it. Unless the
+    /// error already has help text, "Try:" is added above it.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to open database")
+    ///                             .reason("main.db not found")
+    ///                             .help_command("touch main.db");
+    /// ```
+    pub fn help_command<S: Into<String>>(mut self, command: S) -> UserFacingError {
+        if self.helptext.is_none() {
+            self.helptext = Some(Cow::Owned(messages::message(messages::TRY_COMMAND)));
+        }
+        self.decorations_mut().command = Some(Cow::Owned(command.into()));
+        self.modified = true;
+        self
+    }
+
+    // The command suggested by the help text, if any, set apart by its style
+    fn pretty_command(&self) -> Option<String> {
+        let theme = theme::active_theme();
+        self.decorations().command.as_ref().map(|command| {
+            let command = redact::redact(command);
+            let line = [&*theme.command, "$ ", &*command, &*theme.reset].concat();
+            terminal::ascii_safe(line)
+        })
+    }
+
+    /// Add a link to documentation about the error, e.g. a page explaining
+    /// its code. It is printed on its own line below the help text, as
+    /// "See: <url>".
+    /// # Example
+    /// ```
+    /// # use user_error::{UserFacingError, UFE};
+    /// let err = UserFacingError::new("Config file is invalid")
+    ///                             .with_doc_url("https://example.com/errors/E0042");
+    /// assert_eq!(err.doc_url(), Some("https://example.com/errors/E0042"));
+    /// ```
+    pub fn with_doc_url(mut self, url: &'static str) -> UserFacingError {
+        self.decorations_mut().doc_url = Some(url);
+        self
+    }
+
+    /// Add help text suggesting commands to run, as in "Try one of:
+    /// `cargo clean`, `cargo update`". The commands are highlighted so that
+    /// they stand out. Nothing is added if there are no commands.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to build project")
+    ///                             .with_suggestion_commands(&["cargo clean", "cargo update"]);
+    /// ```
+    pub fn with_suggestion_commands(self, commands: &[&str]) -> UserFacingError {
+        let quoted: Vec<String> = commands
+            .iter()
+            .map(|command| ["`", command, "`"].concat())
+            .collect();
+        let helptext = match quoted.as_slice() {
+            [] => return self,
+            [command] => messages::message(messages::SUGGEST_COMMAND)
+                .replace(messages::COMMAND_PLACEHOLDER, command),
+            commands => messages::message(messages::SUGGEST_COMMANDS)
+                .replace(messages::COMMANDS_PLACEHOLDER, &commands.join(", ")),
+        };
+        let keywords: Vec<&str> = quoted.iter().map(String::as_str).collect();
+        self.help(helptext).highlight(&keywords)
+    }
+
+    /// Add a code identifying the error, e.g. "E0042". It is printed in
+    /// brackets in front of the summary.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("File failed to open").with_code("E0042");
+    /// ```
+    pub fn with_code<S: Into<String>>(mut self, code: S) -> UserFacingError {
+        self.decorations_mut().code = Some(Cow::Owned(code.into()));
+        self
+    }
+
+    /// Add a code identifying the error, along with the code the program
+    /// exits with after print_and_exit(), so that each error code always
+    /// maps to the same exit code.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Config file is invalid").code_with_exit("E_CONFIG", 78);
+    /// ```
+    pub fn code_with_exit<S: Into<String>>(self, code: S, exit: i32) -> UserFacingError {
+        let mut error = self.with_code(code);
+        error.decorations_mut().exit_code = Some(exit);
+        error
+    }
+
+    /// Add a footer, printed after everything else in a very dim fashion,
+    /// e.g. where to report bugs. Unlike the help text, it is about the
+    /// program rather than this particular error.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("Failed to build project")
+    ///                             .help("Try: cargo clean")
+    ///                             .footer("Report bugs at https://github.com/me/tool/issues");
+    /// ```
+    pub fn footer<S: Into<String>>(mut self, footer: S) -> UserFacingError {
+        self.decorations_mut().footer = Some(Cow::Owned(footer.into()));
+        self
+    }
+
+    /// Clears all the help text from a UserFacingError.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let mut err = UserFacingError::new("File failed to open")
+    ///                             .reason("File not found")
+    ///                             .reason("Directory cannot be entered")
+    ///                             .help("Check if the file exists.");
+    /// err.clear_helptext();
+    /// ```
+    pub fn clear_helptext(&mut self) {
+        self.helptext = None;
+        if let Some(decorations) = self.decorations.as_mut() {
+            decorations.command = None;
+        }
+        self.modified = true;
+    }
+
+    /// Removes all reasons from a UserFacingError. This is the builder
+    /// counterpart of clear_reasons().
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("File failed to open")
+    ///                             .reason("File not found")
+    ///                             .without_reasons()
+    ///                             .reason("Directory cannot be entered");
+    /// ```
+    pub fn without_reasons(mut self) -> UserFacingError {
+        self.clear_reasons();
+        self
+    }
+
+    /// Removes the help text from a UserFacingError. This is the builder
+    /// counterpart of clear_helptext().
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::new("File failed to open")
+    ///                             .help("Check if the file exists.")
+    ///                             .without_helptext()
+    ///                             .reason("File not found");
+    /// ```
+    pub fn without_helptext(mut self) -> UserFacingError {
+        self.clear_helptext();
+        self
+    }
+
+    /// Returns whether the summary, reasons or help text have changed since
+    /// the last call, and clears the flag. Useful for skipping re-rendering an
+    /// error that hasn't changed, e.g. in a TUI. Building an error with a
+    /// reason or help text counts as a change.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let mut err = UserFacingError::new("File failed to open").reason("File not found");
+    /// assert!(err.take_modified());
+    /// assert!(!err.take_modified());
+    /// err.clear_reasons();
+    /// assert!(err.take_modified());
+    /// ```
+    pub fn take_modified(&mut self) -> bool {
+        std::mem::take(&mut self.modified)
+    }
+
+    /// Attach the underlying error that caused this one, which is returned by
+    /// .source(). The source, and every error in its own .source() chain, are
+    /// added to the list of reasons once, when the source is attached. Since
+    /// the reasons of a UserFacingError are stored rather than derived from
+    /// .source() each time, they are never listed twice. Replaces the
+    /// previous source (if any), but not the reasons it added.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let ioe = std::io::Error::other("File not found");
+    /// let err = UserFacingError::new("Failed to load config").with_source(ioe);
+    /// ```
+    pub fn with_source<E: Error + 'static>(self, source: E) -> UserFacingError {
+        let mut chain = vec![source.to_string()];
+        chain.extend(error_sources(source.source()).unwrap_or_default());
+
+        let mut error = chain
+            .into_iter()
+            .fold(self, |error, reason| error.reason(reason));
+        error.source = Some(Box::new(source));
+        error
+    }
+
+    /// Removes the underlying source error from a UserFacingError, if any.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let ioe = std::io::Error::other("File not found");
+    /// let err = UserFacingError::from(ioe).without_source();
+    /// ```
+    pub fn without_source(mut self) -> UserFacingError {
+        self.source = None;
+        self
+    }
+
+    /// Returns the first error of type T in the source chain: the source
+    /// error, its source, and so on. Useful for branching on the original
+    /// error after it has been converted into a UserFacingError.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// use std::io;
+    /// let err = UserFacingError::from(io::Error::from(io::ErrorKind::WouldBlock));
+    /// let retry = err
+    ///     .source_as::<io::Error>()
+    ///     .is_some_and(|e| e.kind() == io::ErrorKind::WouldBlock);
+    /// assert!(retry);
+    /// ```
+    pub fn source_as<T: Error + 'static>(&self) -> Option<&T> {
+        let mut source = self.source();
+        while let Some(error) = source {
+            /* Look inside errors shared through an Arc as well */
+            let error = match error.downcast_ref::<SharedError>() {
+                Some(shared) => shared.0.as_ref(),
+                None => error,
+            };
+            if let Some(error) = error.downcast_ref::<T>() {
+                return Some(error);
+            }
+            source = error.source();
+        }
+        None
+    }
+
+    /// Returns the kind of the first `std::io::Error` in the source chain,
+    /// if there is one. Useful for control flow after an io error has been
+    /// converted into a UserFacingError.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// use std::io;
+    /// let err = UserFacingError::from(io::Error::from(io::ErrorKind::PermissionDenied));
+    /// assert_eq!(err.io_kind(), Some(io::ErrorKind::PermissionDenied));
+    /// ```
+    pub fn io_kind(&self) -> Option<std::io::ErrorKind> {
+        self.source_as::<std::io::Error>().map(std::io::Error::kind)
+    }
+
+    /// Takes the source error back out of the UserFacingError, if there is
+    /// one.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let ioe = std::io::Error::other("File not found");
+    /// let source = UserFacingError::from(ioe).into_source();
+    /// assert!(source.is_some());
+    /// ```
+    pub fn into_source(self) -> Option<Box<dyn Error>> {
+        self.source
+    }
+
+    /// Takes the source error back out of the UserFacingError if it is of
+    /// type T. Otherwise the UserFacingError is returned unchanged. Useful for
+    /// recovering from the original error, and only presenting the
+    /// UserFacingError if that fails.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// use std::io;
+    /// let err = UserFacingError::from(io::Error::from(io::ErrorKind::WouldBlock));
+    /// match err.downcast_source::<io::Error>() {
+    ///     Ok(ioe) => assert_eq!(ioe.kind(), io::ErrorKind::WouldBlock),
+    ///     Err(err) => panic!("{}", err),
+    /// }
+    /// ```
+    pub fn downcast_source<T: Error + 'static>(mut self) -> Result<T, UserFacingError> {
+        match self.source.take().map(|source| source.downcast::<T>()) {
+            Some(Ok(source)) => Ok(*source),
+            Some(Err(source)) => {
+                self.source = Some(source);
+                Err(self)
+            }
+            None => Err(self),
+        }
+    }
+
+    /// Breaks the error up into plain owned data: its summary, reasons and
+    /// help text. Useful for sending an error across a process or FFI
+    /// boundary, to be rebuilt with from_parts(). The source error, the
+    /// stream and the indentation of the reasons are dropped.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let (summary, reasons, helptext) = UserFacingError::new("File failed to open")
+    ///                             .reason("File not found")
+    ///                             .into_parts();
+    /// assert_eq!(reasons, vec!["File not found"]);
+    /// assert!(helptext.is_none());
+    /// ```
+    pub fn into_parts(mut self) -> (String, Vec<String>, Option<String>) {
+        let summary = std::mem::take(&mut self.summary).into_owned();
+        let helptext = self.helptext.take().map(Cow::into_owned);
+        let reasons = self
+            .into_all_reasons()
+            .into_iter()
+            .map(|reason| reason.text().into_owned())
+            .collect();
+        (summary, reasons, helptext)
+    }
+
+    /// Rebuilds an error from the parts returned by into_parts(). An empty
+    /// list of reasons means the error has none.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let (summary, reasons, helptext) = UserFacingError::new("File failed to open")
+    ///                             .help("Try: touch file.txt")
+    ///                             .into_parts();
+    /// let err = UserFacingError::from_parts(summary, reasons, helptext);
+    /// ```
+    pub fn from_parts(
+        summary: String,
+        reasons: Vec<String>,
+        helptext: Option<String>,
+    ) -> UserFacingError {
+        let mut error = UserFacingError {
+            helptext: helptext.map(Cow::Owned),
+            ..UserFacingError::new(summary)
+        };
+        error.extend(reasons);
+        error
+    }
+
+    /// Collapses the errors of several operations into one, or returns None
+    /// if there are none. A single error is returned as is. Otherwise the
+    /// summary says how many errors occurred, and each error's summary is
+    /// listed as a reason, with its own reasons indented below it. Each
+    /// distinct help text is kept, one per line.
+    /// # Example
+    /// ```
+    /// # use user_error::prelude::*;
+    /// let err = UserFacingError::from_multiple(vec![
+    ///     UserFacingError::new("Failed to open main.db").reason("File not found"),
+    ///     UserFacingError::new("Failed to open config.toml"),
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(err.summary(), "2 errors occurred");
+    /// ```
+    pub fn from_multiple(errors: Vec<UserFacingError>) -> Option<UserFacingError> {
+        if errors.len() <= 1 {
+            return errors.into_iter().next();
+        }
+
+        let count = errors.len();
+        let mut reasons = Vec::new();
+        let mut helptexts: Vec<Cow<'static, str>> = Vec::new();
+        for mut error in errors {
+            /* The summary heads the group of the error's own reasons */
+            reasons.push(Reason::from(std::mem::take(&mut error.summary)));
+            if let Some(helptext) = error.helptext.take() {
+                if !helptexts.contains(&helptext) {
+                    helptexts.push(helptext);
+                }
+            }
+            reasons.extend(error.into_all_reasons().into_iter().map(|reason| Reason {
+                level: reason.level + 1,
+                ..reason
+            }));
+        }
+
+        let helptext = Some(helptexts.join("\n")).filter(|helptext| !helptext.is_empty());
+        Some(UserFacingError {
+            reasons: Some(reasons),
+            helptext: helptext.map(Cow::Owned),
+            ..UserFacingError::new(messages::plural(messages::ERRORS_OCCURRED, count))
+        })
+    }
+
+    /// Returns the error on a single line, without any formatting, for log
+    /// systems that expect one line per entry. The sections are separated by
+    /// ' | ' and the reasons by '; ':
+    /// `Error: <summary> | <reason 1>; <reason 2> | <help text>`
+    /// Trailing empty sections are left out.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let line = UserFacingError::new("File failed to open")
+    ///                             .reason("File not found")
+    ///                             .help("Try: touch file.txt")
+    ///                             .to_compact_string();
+    /// assert_eq!(line, "Error: File failed to open | File not found | Try: touch file.txt");
+    /// ```
+    pub fn to_compact_string(&self) -> String {
+        let mut compact = [COMPACT_LABEL, &self.summary].concat();
+        let reasons = self
+            .reasons()
+            .map(|reasons| reasons.join(COMPACT_REASON_SEPARATOR));
+        if reasons.is_some() || self.helptext.is_some() {
+            compact.push_str(COMPACT_SECTION_SEPARATOR);
+            compact.push_str(reasons.as_deref().unwrap_or_default());
+        }
+        if let Some(helptext) = &self.helptext {
+            compact.push_str(COMPACT_SECTION_SEPARATOR);
+            compact.push_str(helptext);
+        }
+        compact
+    }
+
+    /// Parses a line produced by to_compact_string() back into a
+    /// UserFacingError. Returns None if the line isn't in that format. Text
+    /// containing the separators themselves will not survive the round trip.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let line = "Error: File failed to open | File not found | Try: touch file.txt";
+    /// let err = UserFacingError::from_compact_string(line).unwrap();
+    /// assert_eq!(err.to_compact_string(), line);
+    /// ```
+    pub fn from_compact_string(compact: &str) -> Option<UserFacingError> {
+        let compact = compact.strip_prefix(COMPACT_LABEL)?;
+        let mut sections = compact.split(COMPACT_SECTION_SEPARATOR);
+        let summary = sections.next()?;
+        let reasons: Vec<Reason> = sections
+            .next()
+            .unwrap_or_default()
+            .split(COMPACT_REASON_SEPARATOR)
+            .filter(|reason| !reason.is_empty())
+            .map(|reason| Reason::from(reason.to_string()))
+            .collect();
+        let helptext = sections
+            .next()
+            .map(|helptext| Cow::Owned(helptext.to_string()));
+        if sections.next().is_some() {
+            return None;
+        }
+
+        Some(UserFacingError {
+            reasons: if reasons.is_empty() {
+                None
+            } else {
+                Some(reasons)
+            },
+            helptext,
+            ..UserFacingError::new(summary.to_string())
+        })
+    }
+
+    /// Decodes an error formatted with `{:x}` back into a UserFacingError.
+    /// Returns None if the text isn't in that format. Reasons are no longer
+    /// indented, and everything but the text is lost.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let code = format!("{:x}", UserFacingError::new("File failed to open"));
+    /// let err = UserFacingError::from_hex(&code).unwrap();
+    /// assert_eq!(err.summary(), "File failed to open");
+    /// ```
+    pub fn from_hex(hex: &str) -> Option<UserFacingError> {
+        let json = String::from_utf8(hex::decode(hex)?).ok()?;
+        UserFacingError::from_json(&json)
+    }
+
+    /// Decodes an error encoded with to_json() back into a UserFacingError,
+    /// e.g. one read from a structured log, so that it can be printed again.
+    /// The summary is required; the reasons, help text and footer are
+    /// optional, and any other fields are ignored. Returns None if the text
+    /// isn't JSON in that shape. The source error can't be recovered, so the
+    /// decoded error has none.
+    /// # Example
+    /// ```
+    /// use user_error::prelude::*;
+    /// let json = r#"{"summary":"File failed to open","reasons":["File not found"]}"#;
+    /// let err = UserFacingError::from_json(json).unwrap();
+    /// assert_eq!(err.reasons().unwrap(), vec!["File not found"]);
+    /// ```
+    pub fn from_json(json: &str) -> Option<UserFacingError> {
+        let value = json::parse(json)?;
+        let summary = value.get("summary")?.as_str()?.to_string();
+        let reasons = match value.get("reasons") {
+            Some(json::Value::Array(reasons)) if !reasons.is_empty() => Some(
+                reasons
+                    .iter()
+                    .map(|reason| Some(Reason::from(reason.as_str()?.to_string())))
+                    .collect::<Option<Vec<Reason>>>()?,
+            ),
+            Some(json::Value::Array(_)) | None => None,
+            Some(_) => return None,
+        };
+        let text = |key| match value.get(key) {
+            Some(json::Value::String(text)) => Some(Some(Cow::Owned(text.clone()))),
+            Some(json::Value::Null) | None => Some(None),
+            Some(_) => None,
+        };
+        let helptext = text("helptext")?;
+        let footer = text("footer")?;
+
+        let error = UserFacingError {
+            reasons,
+            helptext,
+            ..UserFacingError::new(summary)
+        };
+        Some(match footer {
+            Some(footer) => error.footer(footer),
+            None => error,
+        })
+    }
+
+    /// Prints the error on a single line, without any formatting. See
+    /// to_compact_string().
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// UserFacingError::new("File failed to open")
+    ///         .reason("File not found")
+    ///         .print_compact();
+    /// ```
+    pub fn print_compact(&self) {
+        let compact = self.to_compact_string();
+        self.stream()
+            .print(&[&*redact::redact(&compact), "\n"].concat());
+    }
+
+    /// Returns the code the program should exit with, e.g. to return it from
+    /// main() once the error has been printed. Exit codes that don't fit in
+    /// an ExitCode are reported as a plain failure.
+    /// # Example
+    /// ```
+    /// # use user_error::{UserFacingError, UFE};
+    /// # use std::process::ExitCode;
+    /// let err = UserFacingError::new("Config file is invalid").code_with_exit("E_CONFIG", 78);
+    /// err.print();
+    /// assert_eq!(err.into_exit_code(), ExitCode::from(78));
+    /// ```
+    pub fn into_exit_code(self) -> ExitCode {
+        u8::try_from(self.exit_code()).map_or(ExitCode::FAILURE, ExitCode::from)
+    }
+
+    /// Print the error to the given stream instead of the default one.
+    /// # Example
+    /// ```
+    /// # use user_error::{Stream, UserFacingError, UFE};
+    /// UserFacingError::new("File failed to open")
+    ///         .on_stream(Stream::Stdout)
+    ///         .print();
+    /// ```
+    pub fn on_stream(mut self, stream: Stream) -> UserFacingError {
+        self.stream = Some(stream);
+        self
+    }
+
+    /// Exit with the given `sysexits.h` code after print_and_exit(), instead
+    /// of 1.
+    /// # Example
+    /// ```no_run
+    /// # use user_error::{SysexitCode, UserFacingError, UFE};
+    /// UserFacingError::new("Config file is missing a [server] section")
+    ///         .with_sysexits_code(SysexitCode::Config)
+    ///         .print_and_exit();
+    /// ```
+    pub fn with_sysexits_code(mut self, code: SysexitCode) -> UserFacingError {
+        self.decorations_mut().exit_code = Some(code.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // Statics to keep the testing DRY/cleaner
+    // The rendered 'Error:' label followed by the summary formatting
+    static SUMMARY_PREFIX: &str = "\u{001b}[97;41;22mError:\u{001b}[91;49;1m ";
+    // ' - ' bullet point in yellow and text in bold white
+    static REASON_PREFIX: &str = "\u{001b}[93;49;1m - \u{001b}[97;49;1m";
+    // Muted white help text
+    static HELPTEXT_PREFIX: &str = "\u{001b}[37;49;2m";
+    // Dim gray footer
+    static FOOTER_PREFIX: &str = "\u{001b}[90;49;2m";
+    // ASCII Reset formatting escape code
+    static RESET: &str = "\u{001b}[0m";
+    static S: &str = "Test Error";
+    static R: &str = "Reason 1";
+    static H: &str = "Try Again";
+
+    // Pins the rendering options that are otherwise detected from the
+    // environment, so that the expected output doesn't depend on the
+    // terminal or locale of whoever runs the tests
+    pub(crate) fn pin_rendering() {
+        set_theme(Theme::dark());
+        set_ascii_only(false);
+        /* Wide enough that nothing the tests render is wrapped */
+        set_width(1000);
+    }
+
+    #[test]
+    fn new_test() {
+        eprintln!("{}", UserFacingError::new("Test Error"));
+    }
+
+    #[test]
+    fn summary_test() {
+        pin_rendering();
+        let e = UserFacingError::new(S);
+        let expected = [SUMMARY_PREFIX, S, RESET].concat();
+        assert_eq!(e.to_string(), expected);
+        eprintln!("{}", e);
+    }
+
+    #[test]
+    fn header_only_test() {
+        let e = UserFacingError::header_only(S);
+        assert_eq!(e.to_string(), UserFacingError::new(S).to_string());
+        assert!(!e.to_string().ends_with('\n'));
+        assert!(e.footer("Footer").to_string().ends_with('\n'));
+    }
+
+    #[test]
+    fn reason_lazy_test() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let calls = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&calls);
+        let e = UserFacingError::new(S).reason_lazy(move || {
+            counter.set(counter.get() + 1);
+            R.to_string()
+        });
+        assert_eq!(calls.get(), 0);
+        let expected = UserFacingError::new(S).reason(R).to_string();
+        assert_eq!(e.to_string(), expected);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(e.reasons(), Some(vec![R.to_string()]));
+    }
+
+    #[test]
+    fn prefix_reasons_test() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let calls = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&calls);
+        let e = UserFacingError::new(S)
+            .reason(R)
+            .reason_indented("Reason 2", 1)
+            .reason_with_help("Reason 3", H)
+            .reason_lazy(move || {
+                counter.set(counter.get() + 1);
+                "Reason 4".to_string()
+            })
+            .prefix_reasons("[db] ")
+            .suffix_reasons(".");
+        assert_eq!(calls.get(), 0);
+        let expected = UserFacingError::new(S)
+            .reason("[db] Reason 1.")
+            .reason_indented("[db] Reason 2.", 1)
+            .reason_with_help("[db] Reason 3.", H)
+            .reason("[db] Reason 4.");
+        assert_eq!(e.to_string(), expected.to_string());
+        assert_eq!(calls.get(), 1);
+
+        let e = UserFacingError::new(S).prefix_reasons("[db] ");
+        assert!(e.reasons().is_none());
+    }
+
+    #[test]
+    fn sysexits_code_test() {
+        assert_eq!(UserFacingError::new(S).exit_code(), 1);
+        let e = UserFacingError::new(S).with_sysexits_code(SysexitCode::DataErr);
+        assert_eq!(e.exit_code(), 65);
+        assert_eq!(i32::from(SysexitCode::Software), 70);
+    }
+
+    #[test]
+    fn from_multiple_test() {
+        assert!(UserFacingError::from_multiple(Vec::new()).is_none());
+
+        let single = UserFacingError::from_multiple(vec![UserFacingError::new(S).reason(R)]);
+        assert_eq!(
+            single.unwrap().to_string(),
+            UserFacingError::new(S).reason(R).to_string()
+        );
+
+        let e = UserFacingError::from_multiple(vec![
+            UserFacingError::new("First").reason(R).help(H),
+            UserFacingError::new("Second").help(H),
+        ])
+        .unwrap();
+        assert_eq!(e.summary(), "2 errors occurred");
+        assert_eq!(e.reasons().unwrap(), vec!["First", R, "Second"]);
+        assert_eq!(e.helptext().unwrap(), H);
+        let levels: Vec<usize> = e.reasons.unwrap().iter().map(|r| r.level).collect();
+        assert_eq!(levels, vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn code_with_exit_test() {
+        let e = UserFacingError::new(S).code_with_exit("E_CONFIG", 78);
+        assert_eq!(e.code().unwrap(), "E_CONFIG");
+        assert_eq!(e.exit_code(), 78);
+        assert_eq!(e.into_exit_code(), ExitCode::from(78));
+
+        let e = UserFacingError::new(S).code_with_exit("E_HUGE", 300);
+        assert_eq!(e.into_exit_code(), ExitCode::FAILURE);
+        assert_eq!(UserFacingError::new(S).into_exit_code(), ExitCode::FAILURE);
+    }
+
+    #[test]
+    fn take_modified_test() {
+        let mut e = UserFacingError::new(S);
+        assert!(!e.take_modified());
+        let mut e = e.reason(R);
+        assert!(e.take_modified());
+        assert!(!e.take_modified());
+        let mut e = e.help(H);
+        assert!(e.take_modified());
+        e.dedup_reasons_with(DedupMode::Full);
+        e.update(S);
+        assert!(e.take_modified());
+        assert!(!e.take_modified());
+    }
+
+    #[test]
+    fn helptext_test() {
+        pin_rendering();
+        let e = UserFacingError::new(S).help(H);
+        let expected = format!(
+            "{}{}{}\n{}{}{}\n",
+            SUMMARY_PREFIX, S, RESET, HELPTEXT_PREFIX, H, RESET
+        );
+        assert_eq!(e.to_string(), expected);
+        eprintln!("{}", e);
+    }
+
+    #[test]
+    fn footer_test() {
+        pin_rendering();
+        let e = UserFacingError::new(S)
+            .footer("Report bugs")
+            .help(H)
+            .reason(R);
+        /* Summary, then reasons, then help text, then footer */
+        let expected = format!(
+            "{}{}{}\n{}{}{}\n{}{}{}\n{}Report bugs{}\n",
+            SUMMARY_PREFIX,
+            S,
+            RESET,
+            REASON_PREFIX,
+            R,
+            RESET,
+            HELPTEXT_PREFIX,
+            H,
+            RESET,
+            FOOTER_PREFIX,
+            RESET
+        );
+        assert_eq!(e.to_string(), expected);
+        assert_eq!(
+            e.to_json(),
+            r#"{"summary":"Test Error","reasons":["Reason 1"],"helptext":"Try Again","footer":"Report bugs"}"#
+        );
+        eprintln!("{}", e);
+    }
+
+    #[test]
+    fn snippet_test() {
+        let e = UserFacingError::new(S).reason(R).help(H).snippet(
+            Path::new("config.toml"),
+            7,
+            8,
+            "port = \"abc\"",
+            5,
+        );
+        let expected = format!(
+            "Error: {}\n - {}\n --> config.toml:7:8\n  |\n7 | port = \"abc\"\n  |        ^^^^^\n{}\n",
+            S, R, H
+        );
+        assert_eq!(terminal::strip_ansi(&e.to_string()), expected);
+    }
+
+    #[test]
+    fn json_pretty_test() {
+        let e = UserFacingError::new(S)
+            .reason(R)
+            .help(H)
+            .footer("Report bugs");
+        let pretty = e.to_json_pretty();
+        assert!(pretty.contains("\n  \"reasons\": [\n    \"Reason 1\"\n  ],\n"));
+        assert_eq!(json::parse(&pretty), json::parse(&e.to_json()));
+    }
+
+    #[test]
+    fn hex_test() {
+        let e = UserFacingError::new(S)
+            .reason(R)
+            .help(H)
+            .footer("Report bugs");
+        let hex = format!("{:x}", e);
+        assert!(hex.bytes().all(|byte| byte.is_ascii_hexdigit()));
+        let decoded = UserFacingError::from_hex(&hex).unwrap();
+        assert_eq!(decoded.to_json(), e.to_json());
+
+        let hex = format!("{:x}", UserFacingError::new(S));
+        let decoded = UserFacingError::from_hex(&hex).unwrap();
+        assert_eq!(decoded.summary(), S);
+        assert!(decoded.reasons().is_none());
+        assert!(decoded.helptext().is_none());
+
+        assert!(UserFacingError::from_hex("7b7d").is_none());
+        assert!(UserFacingError::from_hex("not hex").is_none());
+    }
+
+    #[test]
+    fn from_json_test() {
+        let e = UserFacingError::new(S)
+            .reason(R)
+            .help(H)
+            .footer("Report bugs")
+            .with_source(std::io::Error::from(std::io::ErrorKind::NotFound));
+        let decoded = UserFacingError::from_json(&e.to_json()).unwrap();
+        assert_eq!(decoded.summary(), S);
+        assert_eq!(decoded.reasons().unwrap(), vec![R, "entity not found"]);
+        assert_eq!(decoded.helptext().unwrap(), H);
+        assert_eq!(UFE::footer(&decoded).unwrap(), "Report bugs");
+        assert!(decoded.source().is_none());
+
+        let decoded = UserFacingError::from_json(r#"{"summary":"Failed"}"#).unwrap();
+        assert!(decoded.reasons().is_none());
+        assert!(decoded.helptext().is_none());
+        /* Fields it doesn't know are ignored, whatever their type */
+        let json = r#"{"summary":"Failed","exit_code":2,"fatal":true,"at":{"line":3}}"#;
+        assert_eq!(
+            UserFacingError::from_json(json).unwrap().summary(),
+            "Failed"
+        );
+
+        assert!(UserFacingError::from_json(r#"{"reasons":[]}"#).is_none());
+        assert!(UserFacingError::from_json(r#"{"summary":"Failed","helptext":[]}"#).is_none());
+        assert!(UserFacingError::from_json("not json").is_none());
+    }
+
+    #[test]
+    fn suggestion_command_test() {
+        let e = UserFacingError::new(S).with_suggestion_command("cargo clean");
+        assert_eq!(e.helptext().unwrap(), "Try: `cargo clean`");
+
+        let e = UserFacingError::new(S).with_suggestion_commands(&["cargo clean", "cargo update"]);
+        assert_eq!(
+            e.helptext().unwrap(),
+            "Try one of: `cargo clean`, `cargo update`"
+        );
+
+        let e = UserFacingError::new(S)
+            .help(H)
+            .with_suggestion_commands(&[]);
+        assert_eq!(e.helptext().unwrap(), H);
+    }
+
+    #[test]
+    fn doc_url_test() {
+        pin_rendering();
+        let url = "https://example.com/errors/E0042";
+        let e = UserFacingError::new(S).help(H).with_doc_url(url);
+        let link = [
+            "\u{001b}]8;;",
+            url,
+            "\u{001b}\\",
+            url,
+            "\u{001b}]8;;\u{001b}\\",
+        ]
+        .concat();
+        let expected = format!(
+            "{}{}{}\n{}{}{}\n{}See: {}{}\n",
+            SUMMARY_PREFIX, S, RESET, HELPTEXT_PREFIX, H, RESET, HELPTEXT_PREFIX, link, RESET
+        );
+        assert_eq!(e.to_string(), expected);
+        assert_eq!(e.doc_url(), Some(url));
+
+        /* Independent of the help text */
+        let e = UserFacingError::new(S).with_doc_url(url);
+        assert!(e
+            .render_plain()
+            .ends_with("\nSee: https://example.com/errors/E0042\n"));
+        assert_eq!(UserFacingError::new(S).doc_url(), None);
+    }
+
+    #[test]
+    fn help_command_test() {
+        pin_rendering();
+        let e = UserFacingError::new(S)
+            .help(H)
+            .help_command("touch main.db");
+        let command = "\u{001b}[30;47;22m$ touch main.db";
+        let expected = format!(
+            "{}{}{}\n{}{}{}\n{}{}\n",
+            SUMMARY_PREFIX, S, RESET, HELPTEXT_PREFIX, H, RESET, command, RESET
+        );
+        assert_eq!(e.to_string(), expected);
+
+        let mut e = UserFacingError::new(S).help_command("touch main.db");
+        assert_eq!(e.helptext().unwrap(), "Try:");
+        e.clear_helptext();
+        assert!(!e.to_string().contains("touch main.db"));
+    }
+
+    #[test]
+    fn if_some_test() {
+        let e = UserFacingError::new(S)
+            .reason_if_some(Some(R))
+            .help_if_some(Some(H));
+        assert_eq!(e.reasons().unwrap(), vec![R]);
+        assert_eq!(e.helptext().unwrap(), H);
+
+        let e = UserFacingError::new(S)
+            .help(H)
+            .reason_if_some(None::<&str>)
+            .help_if_some(None::<String>);
+        assert!(e.reasons().is_none());
+        assert_eq!(e.helptext().unwrap(), H);
     }
 
     #[test]
     fn reason_test() {
+        pin_rendering();
         let e = UserFacingError::new(S).reason(R).reason(R);
 
         /* Create Reasons String */
@@ -517,7 +3567,132 @@ mod tests {
     }
 
     #[test]
+    fn reason_with_help_test() {
+        pin_rendering();
+        let e = UserFacingError::new(S)
+            .reason_with_help("Reason 1", "Help 1")
+            .reason_with_help("Reason 2", "Help 2")
+            .help(H);
+
+        let reason = |text: &str, help: &str| {
+            [REASON_PREFIX, text, "\n   ", HELPTEXT_PREFIX, help, RESET].concat()
+        };
+        let expected = [
+            SUMMARY_PREFIX,
+            S,
+            RESET,
+            "\n",
+            &reason("Reason 1", "Help 1"),
+            "\n",
+            &reason("Reason 2", "Help 2"),
+            RESET,
+            "\n",
+            HELPTEXT_PREFIX,
+            H,
+            RESET,
+            "\n",
+        ]
+        .concat();
+        assert_eq!(e.to_string(), expected);
+    }
+
+    #[test]
+    fn common_constructors_test() {
+        let e = UserFacingError::from_env_missing("DATABASE_URL");
+        assert_eq!(e.summary(), "Missing required environment variable");
+        assert_eq!(e.reasons().unwrap(), vec!["'DATABASE_URL' is not set"]);
+        assert_eq!(
+            e.helptext().unwrap(),
+            "Set it with: export DATABASE_URL=<value>"
+        );
+
+        let e = UserFacingError::from_file_not_found(Path::new("data/main.db"));
+        assert_eq!(e.summary(), "File not found");
+        assert_eq!(e.reasons().unwrap(), vec!["data/main.db"]);
+        assert_eq!(e.helptext().unwrap(), "Check that the file exists");
+
+        let e = UserFacingError::from_permission_denied(Path::new("data/main.db"));
+        assert_eq!(e.summary(), "Permission denied");
+        assert_eq!(e.reasons().unwrap(), vec!["Cannot access 'data/main.db'"]);
+        assert_eq!(
+            e.helptext().unwrap(),
+            messages::message("permission-denied-help")
+        );
+    }
+
+    #[test]
+    fn from_parse_failure_test() {
+        let e = UserFacingError::from_parse_failure("12:60", "HH:MM");
+        assert_eq!(e.summary(), "Failed to parse input");
+        assert_eq!(
+            e.reasons().unwrap(),
+            vec!["Input '12:60' does not match expected format: HH:MM"]
+        );
+        assert_eq!(
+            e.helptext().unwrap(),
+            "Check the input format and try again"
+        );
+
+        let long = "x".repeat(100);
+        let e = UserFacingError::from_parse_failure(&long, "a number");
+        let reason = format!(
+            "Input '{}...' does not match expected format: a number",
+            "x".repeat(80)
+        );
+        assert_eq!(e.reasons().unwrap(), vec![reason]);
+
+        let e = UserFacingError::from_parse_failure_at("12:60", 3, "HH:MM");
+        assert_eq!(e.reasons().unwrap()[1], "At position 3: '60'");
+        assert_eq!(truncated("caf\u{e9}", 3), "caf...");
+        assert_eq!(truncated("caf\u{e9}", 4), "caf\u{e9}");
+    }
+
+    #[test]
+    fn from_colon_split_test() {
+        let e = UserFacingError::from_colon_split("Failed to open file: permission denied");
+        assert_eq!(e.summary(), "Failed to open file");
+        assert_eq!(e.reasons().unwrap(), vec!["permission denied"]);
+
+        let e = UserFacingError::from_colon_split("a: b: c");
+        assert_eq!(e.summary(), "a");
+        assert_eq!(e.reasons().unwrap(), vec!["b: c"]);
+
+        let e = UserFacingError::from_colon_split("Failed");
+        assert_eq!(e.summary(), "Failed");
+        assert!(e.reasons().is_none());
+    }
+
+    #[test]
+    fn show_reasons_test() {
+        pin_rendering();
+        let e = UserFacingError::new(S)
+            .reason(R)
+            .reason_with_help(R, H)
+            .help(H)
+            .show_reasons(false);
+        let expected = [
+            SUMMARY_PREFIX,
+            S,
+            RESET,
+            "\n",
+            HELPTEXT_PREFIX,
+            H,
+            RESET,
+            "\n",
+        ]
+        .concat();
+        assert_eq!(e.to_string(), expected);
+        assert!(!e.to_string().contains(" - "));
+        assert_eq!(e.reasons().unwrap().len(), 2);
+
+        let e = e.show_reasons(true);
+        assert!(e.to_string().contains(R));
+    }
+
+    #[test]
+    #[allow(deprecated)]
     fn push_test() {
+        pin_rendering();
         let mut e = UserFacingError::new(S).reason("R1");
         e.push("R2");
 
@@ -538,7 +3713,22 @@ mod tests {
     }
 
     #[test]
+    fn wrap_with_test() {
+        let wrapped = UserFacingError::new(S).reason("R1").wrap_with("R2");
+        #[allow(deprecated)]
+        let pushed = {
+            let mut e = UserFacingError::new(S).reason("R1");
+            e.push("R2");
+            e
+        };
+        assert_eq!(wrapped.summary(), "R2");
+        assert_eq!(wrapped.to_string(), pushed.to_string());
+    }
+
+    #[test]
+    #[allow(deprecated)]
     fn push_test_empty() {
+        pin_rendering();
         let mut e = UserFacingError::new(S);
         e.push("S2");
 
@@ -558,8 +3748,372 @@ mod tests {
         eprintln!("{}", e);
     }
 
+    #[test]
+    fn normalize_summary_test() {
+        let e = UserFacingError::new("failed to open file.").normalize_summary();
+        assert_eq!(e.summary(), "Failed to open file");
+
+        let e = UserFacingError::new("\u{e9}chec de lecture")
+            .normalize_summary_with(SummaryStyle::Period);
+        assert_eq!(e.summary(), "\u{c9}chec de lecture.");
+
+        let e = UserFacingError::new("what?").normalize_summary_with(SummaryStyle::Period);
+        assert_eq!(e.summary(), "What?");
+    }
+
+    #[test]
+    fn repeat_summary_as_reason_test() {
+        let mut e = UserFacingError::new("detail")
+            .reason(R)
+            .repeat_summary_as_reason();
+        e.update(S);
+        assert_eq!(e.summary(), S);
+        assert_eq!(e.reasons(), Some(vec!["detail".to_string(), R.to_string()]));
+
+        let e = UserFacingError::new("detail")
+            .reason(R)
+            .repeat_summary_as_reason_at(ReasonPosition::Last);
+        assert_eq!(e.summary(), "detail");
+        assert_eq!(e.reasons(), Some(vec![R.to_string(), "detail".to_string()]));
+    }
+
+    #[test]
+    fn summary_only_to_string_test() {
+        pin_rendering();
+        let e = UserFacingError::new(S).reason(R).help(H);
+        let expected = [SUMMARY_PREFIX, S, RESET].concat();
+        assert_eq!(e.summary_only_to_string(), expected);
+    }
+
+    #[test]
+    fn reasons_indexed_test() {
+        pin_rendering();
+        let reasons: Vec<String> = (1..=3).map(|i| format!("R{}", i)).collect();
+        let numbered = pretty_reasons_indexed(Some(reasons), 1).unwrap();
+        let expected = [
+            "\u{001b}[93;49;1m 1. \u{001b}[97;49;1mR1\n",
+            "\u{001b}[93;49;1m 2. \u{001b}[97;49;1mR2\n",
+            "\u{001b}[93;49;1m 3. \u{001b}[97;49;1mR3",
+            RESET,
+        ]
+        .concat();
+        assert_eq!(numbered, expected);
+
+        /* Indices are padded to the widest one */
+        let reasons: Vec<String> = (0..4).map(|i| format!("R{}", i)).collect();
+        let numbered = pretty_reasons_indexed(Some(reasons), 8).unwrap();
+        assert!(numbered.contains(" 08. "));
+        assert!(numbered.contains(" 11. "));
+
+        assert_eq!(pretty_reasons_indexed(None, 1), None);
+    }
+
+    #[test]
+    fn shorthand_constructors_test() {
+        let full = UserFacingError::new(S).reason(R).help(H).to_string();
+        assert_eq!(UserFacingError::new_full(S, R, H).to_string(), full);
+        let e = UserFacingError::new_with_help(S, H);
+        assert_eq!(e.to_string(), UserFacingError::new(S).help(H).to_string());
+        let e = UserFacingError::new_with_reason(S, R);
+        assert_eq!(e.to_string(), UserFacingError::new(S).reason(R).to_string());
+    }
+
+    #[test]
+    fn dedup_reasons_test() {
+        let e = || {
+            UserFacingError::new(S)
+                .reason_list(&["R1", "R1", "R2", "R1"])
+                .reason_indented("R2", 1)
+        };
+        let mut adjacent = e();
+        adjacent.dedup_reasons_with(DedupMode::Adjacent);
+        assert_eq!(adjacent.reasons().unwrap(), vec!["R1", "R2", "R1", "R2"]);
+
+        let mut full = e();
+        full.dedup_reasons_with(DedupMode::Full);
+        assert_eq!(full.reasons().unwrap(), vec!["R1", "R2", "R2"]);
+    }
+
+    #[test]
+    fn reason_list_test() {
+        let e = UserFacingError::new(S).reason_list(&["R1", "R2", "R3"]);
+        let expected = UserFacingError::new(S)
+            .reason("R1")
+            .reason("R2")
+            .reason("R3");
+        assert_eq!(e.to_string(), expected.to_string());
+        assert_eq!(e.reasons().unwrap(), vec!["R1", "R2", "R3"]);
+    }
+
+    #[test]
+    fn reasons_from_lines_test() {
+        let e = UserFacingError::new(S)
+            .reason(R)
+            .reasons_from_lines("Line 1\n   \nLine 2\r\nLine 3\n");
+        let expected = vec![R, "Line 1", "Line 2", "Line 3"];
+        assert_eq!(e.reasons().unwrap(), expected);
+    }
+
+    #[test]
+    fn size_test() {
+        /* Small enough for clippy not to flag Results carrying one */
+        assert!(size_of::<UserFacingError>() < 128);
+    }
+
+    #[test]
+    fn with_age_test() {
+        pin_rendering();
+        let age = Duration::from_millis(2_340);
+        let note = format!(" {}(occurred 2.3s ago){}", HELPTEXT_PREFIX, RESET);
+        assert_eq!(
+            with_age("Error: Failed\n - R\n", age),
+            format!("Error: Failed{}\n - R\n", note)
+        );
+        assert_eq!(
+            with_age("Error: Failed", age),
+            format!("Error: Failed{}", note)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "timestamp")]
+    fn age_test() {
+        let e = UserFacingError::new(S);
+        assert!(e.age().unwrap() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn borrowed_summary_test() {
+        let is_borrowed = |e: &UserFacingError| matches!(e.summary, Cow::Borrowed(_));
+        assert!(is_borrowed(&UserFacingError::new_static("literal")));
+        assert!(!is_borrowed(&UserFacingError::new(format!("{}", 42))));
+        /* new() can't tell a literal from shorter-lived text, so it copies */
+        assert!(!is_borrowed(&UserFacingError::new("literal")));
+
+        let e = UserFacingError::new_static(S)
+            .reason_static(R)
+            .help_static(H);
+        let reasons = e.reasons.unwrap();
+        assert!(matches!(
+            reasons[0].text,
+            ReasonText::Eager(Cow::Borrowed(_))
+        ));
+        assert!(matches!(e.helptext, Some(Cow::Borrowed(_))));
+
+        /* Borrowed text that isn't 'static is still accepted, and copied */
+        let line = String::from("Reason 2");
+        let e = UserFacingError::new(&line[..]).reason(line.as_str());
+        assert_eq!(e.summary(), "Reason 2");
+        assert!(!is_borrowed(&e));
+    }
+
+    #[test]
+    fn extend_test() {
+        let mut e = UserFacingError::new(S);
+        e.extend(vec![String::from(R)]);
+        e.extend(vec!["Line 1", "Line 2"]);
+        e.extend(vec![(String::from("key"), String::from("value"))]);
+        let expected = vec![R, "Line 1", "Line 2", "key: value"];
+        assert_eq!(e.reasons().unwrap(), expected);
+    }
+
+    #[test]
+    fn from_iter_test() {
+        let e: UserFacingError = vec![String::from(R)].into_iter().collect();
+        assert_eq!(e.summary(), "Something went wrong");
+        assert_eq!(e.reasons().unwrap(), vec![R]);
+        assert_eq!(e.with_summary(S).summary(), S);
+
+        let pairs = vec![
+            (String::from(S), String::from("R1")),
+            (String::from("Ignored"), String::from("R2")),
+        ];
+        let e: UserFacingError = pairs.into_iter().collect();
+        assert_eq!(e.summary(), S);
+        assert_eq!(e.reasons().unwrap(), vec!["R1", "R2"]);
+
+        let e: UserFacingError = Vec::<(String, String)>::new().into_iter().collect();
+        assert_eq!(e.summary(), "Something went wrong");
+        assert!(e.reasons().is_none());
+    }
+
+    #[test]
+    fn parts_test() {
+        let errors = vec![
+            UserFacingError::new(S),
+            UserFacingError::new(S).reason(R).reason(R),
+            UserFacingError::new(S).help(H),
+            UserFacingError::new(S).reason(R).help(H),
+        ];
+        for e in errors {
+            let expected = e.to_string();
+            let (summary, reasons, helptext) = e.into_parts();
+            let e = UserFacingError::from_parts(summary, reasons, helptext);
+            assert_eq!(e.to_string(), expected);
+        }
+        let e = UserFacingError::from_parts(S.to_string(), Vec::new(), None);
+        assert!(e.reasons.is_none());
+    }
+
+    #[cfg(feature = "timestamp")]
+    #[test]
+    fn timestamp_test() {
+        use std::time::{Duration, UNIX_EPOCH};
+        let mut e = UserFacingError::new(S).timestamp(true);
+        e.decorations_mut().clock = Some(|| UNIX_EPOCH + Duration::from_secs(1_704_110_400));
+        let expected = [
+            HELPTEXT_PREFIX,
+            "[2024-01-01T12:00:00Z]",
+            RESET,
+            " ",
+            SUMMARY_PREFIX,
+            S,
+            RESET,
+        ]
+        .concat();
+        assert_eq!(e.to_string(), expected);
+        assert!(e.timestamp(false).decorations().clock.is_none());
+    }
+
+    #[test]
+    fn from_tuple_test() {
+        let e = UserFacingError::from((S, vec![R]));
+        assert_eq!(e.to_string(), UserFacingError::new(S).reason(R).to_string());
+
+        let e = UserFacingError::from((S.to_string(), Vec::new(), H.to_string()));
+        assert!(e.reasons.is_none());
+        assert_eq!(e.helptext().unwrap(), H);
+
+        let e = UserFacingError::from((S, vec![R, R], H));
+        assert_eq!(e.reasons().unwrap(), vec![R, R]);
+        assert_eq!(e.helptext().unwrap(), H);
+    }
+
+    #[test]
+    fn from_infallible_test() {
+        fn parse<T, E>(text: &str) -> Result<T, UserFacingError>
+        where
+            T: std::str::FromStr<Err = E>,
+            E: Into<UserFacingError>,
+        {
+            text.parse().map_err(Into::into)
+        }
+        let parsed: String = parse::<String, Infallible>(S).unwrap();
+        assert_eq!(parsed, S);
+    }
+
+    #[test]
+    fn help_from_kind_test() {
+        use std::io::{Error, ErrorKind};
+        let e = UserFacingError::from(Error::from(ErrorKind::NotFound));
+        assert_eq!(
+            e.helptext().unwrap(),
+            "Check the path is correct and the file exists."
+        );
+        let e = UserFacingError::from(Error::from(ErrorKind::PermissionDenied));
+        assert_eq!(
+            e.helptext().unwrap(),
+            "Try running with elevated privileges."
+        );
+        let e = UserFacingError::from(Error::from(ErrorKind::InvalidData));
+        assert!(e.helptext().is_none());
+        assert!(e.reasons().is_none());
+    }
+
+    #[test]
+    fn reason_from_kind_test() {
+        use std::io::{Error, ErrorKind};
+        let e = UserFacingError::from(Error::from(ErrorKind::Unsupported));
+        assert_eq!(
+            e.reasons().unwrap(),
+            vec!["This operation is not supported on this platform"]
+        );
+        let e = UserFacingError::from(Error::from(ErrorKind::OutOfMemory));
+        assert_eq!(
+            e.reasons().unwrap(),
+            vec!["There was not enough memory to complete the operation"]
+        );
+        let e = UserFacingError::from(Error::from(ErrorKind::StorageFull));
+        assert_eq!(
+            e.helptext().unwrap(),
+            "Free up some disk space and try again."
+        );
+    }
+
+    #[test]
+    fn from_result_test() {
+        let ok: Result<(), std::io::Error> = Ok(());
+        assert!(UserFacingError::from_result(ok).is_none());
+        let err: Result<(), std::io::Error> = Err(std::io::Error::other(S));
+        assert_eq!(UserFacingError::from_result(err).unwrap().summary(), S);
+
+        assert!(UserFacingError::from_result_or(Ok::<(), &str>(()), S).is_none());
+        let e = UserFacingError::from_result_or(Err::<(), &str>(R), S).unwrap();
+        assert_eq!(e.summary(), S);
+        assert_eq!(e.reasons().unwrap(), vec![R]);
+    }
+
+    #[test]
+    fn highlight_test() {
+        pin_rendering();
+        let e = UserFacingError::new(S)
+            .reason("File main.db not found")
+            .help("Create main.db")
+            .highlight(&["main.db"]);
+        let accent = "\u{001b}[96;49;1m";
+        let reason_style = "\u{001b}[97;49;1m";
+        let expected = [
+            SUMMARY_PREFIX,
+            S,
+            RESET,
+            "\n",
+            REASON_PREFIX,
+            "File ",
+            accent,
+            "main.db",
+            reason_style,
+            " not found",
+            RESET,
+            "\n",
+            HELPTEXT_PREFIX,
+            "Create ",
+            accent,
+            "main.db",
+            HELPTEXT_PREFIX,
+            RESET,
+            "\n",
+        ]
+        .concat();
+        assert_eq!(e.to_string(), expected);
+    }
+
+    #[test]
+    fn boxed_test() {
+        let e = UserFacingError::new(S).reason(R).help(H).boxed(true);
+        let rendered = e.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 5);
+        let widths: Vec<usize> = lines
+            .iter()
+            .map(|line| wrap::display_width(&terminal::strip_ansi(line)))
+            .collect();
+        assert!(widths.iter().all(|&width| width == widths[0]));
+        assert!(lines[1].contains(S) && lines[2].contains(R) && lines[3].contains(H));
+        assert!(!e.boxed(false).to_string().contains('\u{2502}'));
+    }
+
+    #[test]
+    fn write_summary_test() {
+        use std::fmt::Write;
+        let mut e = UserFacingError::new("");
+        write!(e, "Failed at position {}", 42).unwrap();
+        e.write_char('!').unwrap();
+        assert_eq!(e.summary(), "Failed at position 42!");
+    }
+
     #[test]
     fn reason_and_helptext_test() {
+        pin_rendering();
         let e = UserFacingError::new(S).reason(R).reason(R).help(H);
 
         // Create Reasons String
@@ -582,10 +4136,87 @@ mod tests {
         eprintln!("{}", e);
     }
 
+    #[test]
+    fn without_test() {
+        pin_rendering();
+        let ioe = std::io::Error::other("Error");
+        let e = UserFacingError::from(ioe)
+            .reason(R)
+            .help(H)
+            .without_reasons()
+            .without_helptext()
+            .without_source();
+
+        assert!(e.reasons.is_none());
+        assert!(e.helptext.is_none());
+        assert!(e.source().is_none());
+
+        let e = e.reason(R);
+        let expected = format!(
+            "{}{}{}\n{}{}{}\n",
+            SUMMARY_PREFIX, "Error", RESET, REASON_PREFIX, R, RESET
+        );
+        assert_eq!(e.to_string(), expected);
+    }
+
+    #[test]
+    fn reason_indented_test() {
+        pin_rendering();
+        let e = UserFacingError::new(S)
+            .reason_indented("L0", 0)
+            .reason_indented("L1", 1)
+            .reason_indented("L2", 2);
+
+        let rendered = e.to_string();
+        let lines: Vec<&str> = rendered.lines().skip(1).collect();
+        assert_eq!(lines.len(), 3);
+        for (level, line) in lines.iter().enumerate() {
+            let spaces = line.len() - line.trim_start_matches(' ').len();
+            assert_eq!(spaces, level * 2);
+            /* Color escape codes come after the indentation */
+            assert!(line[spaces..].starts_with(REASON_PREFIX));
+        }
+        eprintln!("{}", e);
+    }
+
+    #[test]
+    fn compact_string_test() {
+        let e = UserFacingError::new(S).reason("R1").reason("R2").help(H);
+        let compact = e.to_compact_string();
+        assert_eq!(compact, "Error: Test Error | R1; R2 | Try Again");
+        assert!(!compact.contains('\u{001b}'));
+
+        /* Round trips, with and without the optional sections */
+        let round_trip = UserFacingError::from_compact_string(&compact).unwrap();
+        assert_eq!(round_trip.to_string(), e.to_string());
+        for compact in ["Error: S", "Error: S | R1", "Error: S |  | H"] {
+            let e = UserFacingError::from_compact_string(compact).unwrap();
+            assert_eq!(e.to_compact_string(), compact);
+        }
+        assert!(UserFacingError::from_compact_string("S |  | H").is_none());
+        assert!(UserFacingError::from_compact_string("Error: S | R | H | ?").is_none());
+    }
+
+    #[test]
+    fn with_source_test() {
+        let e = UserFacingError::new(S).reason(R).with_source(SuperError {
+            side: SuperErrorSideKick,
+        });
+
+        assert_eq!(e.source().unwrap().to_string(), "SuperError");
+        let expected = vec![R, "SuperError", "Sidekick"];
+        assert_eq!(e.reasons().unwrap(), expected);
+
+        /* The reasons derived from the chain are the same as the ones stored */
+        let chain = error_sources(e.source()).unwrap();
+        assert_eq!(e.reasons().unwrap()[1..], chain[..]);
+    }
+
     #[test]
     fn from_error_test() {
+        pin_rendering();
         let error_text = "Error";
-        let ioe = std::io::Error::new(std::io::ErrorKind::Other, error_text);
+        let ioe = std::io::Error::other(error_text);
 
         // Lose the type
         fn de(ioe: std::io::Error) -> Box<dyn Error> {
@@ -594,12 +4225,13 @@ mod tests {
         // Convert to UFE
         let ufe: UserFacingError = de(ioe).into();
 
-        let expected = [SUMMARY_PREFIX, error_text, RESET, "\n"].concat();
+        let expected = [SUMMARY_PREFIX, error_text, RESET].concat();
         assert_eq!(ufe.to_string(), expected);
     }
 
     #[test]
     fn from_error_source_test() {
+        pin_rendering();
         let ufe: UserFacingError = get_super_error().into();
         let expected = [
             SUMMARY_PREFIX,
@@ -616,6 +4248,18 @@ mod tests {
         assert_eq!(ufe.to_string(), expected);
     }
 
+    #[test]
+    fn from_shared_error_test() {
+        let shared: Arc<dyn Error + Send + Sync> = Arc::new(SuperError {
+            side: SuperErrorSideKick,
+        });
+        let ufe = UserFacingError::from(Arc::clone(&shared));
+        assert_eq!(ufe.summary(), "SuperError");
+        assert_eq!(ufe.reasons().unwrap(), vec!["Sidekick"]);
+        assert_eq!(ufe.source().unwrap().to_string(), "SuperError");
+        assert_eq!(Arc::strong_count(&shared), 2);
+    }
+
     // Used for to test that source is working correctly
     #[derive(Debug)]
     struct SuperError {
@@ -664,7 +4308,7 @@ mod tests {
 
     impl Display for MyError {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(f, "{}", self.mssg.to_string())
+            write!(f, "{}", self.mssg)
         }
     }
 
@@ -676,6 +4320,126 @@ mod tests {
 
     impl UFE for MyError {}
 
+    // Error type that only sets the constants
+    #[derive(Debug)]
+    struct NotFound;
+
+    impl Display for NotFound {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", S)
+        }
+    }
+
+    impl Error for NotFound {}
+
+    impl UFEConsts for NotFound {
+        const HELP: Option<&'static str> = Some(H);
+        const CODE: Option<&'static str> = Some("E404");
+    }
+
+    #[test]
+    fn associated_constants_test() {
+        pin_rendering();
+        let expected = [
+            SUMMARY_PREFIX,
+            "[E404] ",
+            S,
+            RESET,
+            "\n",
+            HELPTEXT_PREFIX,
+            H,
+            RESET,
+            "\n",
+        ]
+        .concat();
+        assert_eq!(NotFound.to_pretty_string(), expected);
+        assert_eq!(NotFound.into_ufe().to_string(), expected);
+        assert_eq!(
+            MyError {
+                mssg: S.into(),
+                src: None
+            }
+            .code(),
+            None
+        );
+    }
+
+    #[test]
+    fn trait_object_test() {
+        let errors: Vec<Box<dyn UFE>> = vec![Box::new(UserFacingError::new(S)), Box::new(NotFound)];
+        let summaries: Vec<String> = errors.iter().map(|error| error.summary()).collect();
+        assert_eq!(summaries, vec![S, S]);
+        assert_eq!(errors[1].code().unwrap(), "E404");
+        assert_eq!(errors[1].to_pretty_string(), NotFound.to_pretty_string());
+    }
+
+    #[test]
+    fn os_error_code_test() {
+        /* ENOENT on Unix, ERROR_FILE_NOT_FOUND on Windows */
+        let ioe = std::io::Error::from_raw_os_error(2);
+        assert_eq!(ioe.kind(), std::io::ErrorKind::NotFound);
+        let message = ioe.to_string();
+        let reasons = UserFacingError::from(ioe).reasons().unwrap();
+        let expected = format!("OS error code: 2 ({})", message);
+        assert_eq!(reasons.last(), Some(&expected));
+
+        let ioe = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let reasons = UserFacingError::from(ioe).reasons().unwrap_or_default();
+        assert!(!reasons
+            .iter()
+            .any(|reason| reason.starts_with("OS error code")));
+    }
+
+    #[test]
+    fn io_kind_test() {
+        let ioe = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        let e = UserFacingError::from(ioe);
+        assert_eq!(e.io_kind(), Some(std::io::ErrorKind::PermissionDenied));
+        assert!(e.source_as::<std::io::Error>().is_some());
+
+        assert_eq!(UserFacingError::new(S).io_kind(), None);
+    }
+
+    #[test]
+    fn downcast_source_test() {
+        let ioe = std::io::Error::from(std::io::ErrorKind::WouldBlock);
+        let e = UserFacingError::new(S).with_source(ioe);
+        let ioe = e.downcast_source::<std::io::Error>().unwrap();
+        assert_eq!(ioe.kind(), std::io::ErrorKind::WouldBlock);
+
+        let ioe = std::io::Error::from(std::io::ErrorKind::WouldBlock);
+        let e = UserFacingError::new(S).with_source(ioe).help(H);
+        let expected = e.to_string();
+        let e = e.downcast_source::<SuperError>().unwrap_err();
+        assert_eq!(e.to_string(), expected);
+        assert!(e.source_as::<std::io::Error>().is_some());
+        assert!(e.into_source().is_some());
+
+        let e = UserFacingError::new(S);
+        assert!(e.downcast_source::<std::io::Error>().is_err());
+    }
+
+    #[test]
+    fn source_as_test() {
+        let ioe = std::io::Error::from(std::io::ErrorKind::WouldBlock);
+        let me = MyError {
+            mssg: "Level 1".into(),
+            src: Some(Box::new(ioe)),
+        };
+        let e = UserFacingError::new(S).with_source(me);
+        let ioe = e.source_as::<std::io::Error>().unwrap();
+        assert_eq!(ioe.kind(), std::io::ErrorKind::WouldBlock);
+        assert_eq!(e.source_as::<MyError>().unwrap().mssg, "Level 1");
+        assert!(e.source_as::<SuperError>().is_none());
+
+        let shared: Arc<dyn Error + Send + Sync> = Arc::new(SuperError {
+            side: SuperErrorSideKick,
+        });
+        let e = UserFacingError::from(shared);
+        assert!(e.source_as::<SuperError>().is_some());
+        assert!(e.source_as::<SuperErrorSideKick>().is_some());
+    }
+
     #[test]
     fn custom_error_implements_ufe() {
         let me = MyError {
@@ -0,0 +1,148 @@
+// Iterating over the rendered lines of an error, for custom formatters.
+
+use crate::{
+    join_sections, pretty_doc_url, pretty_footer, pretty_helptext, pretty_invocation,
+    pretty_more_reasons, pretty_reason_list, pretty_summary_stamped, shown_reasons, terminal,
+    UserFacingError, UFE,
+};
+
+/// Which section of the error the next lines come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineState {
+    Summary,
+    Reason(usize),
+    Context,
+    Helptext,
+    Environment,
+    Footer,
+    Done,
+}
+
+/// An iterator over the lines of a rendered UserFacingError, without any
+/// ANSI escape codes: the summary first, then each reason, then any
+/// highlighted value, source snippets and suggested fixes, then the help
+/// text, then any environment snapshot, then the footer. Text that is
+/// wrapped to the terminal width spans several lines.
+/// # Example
+/// ```
+/// # use user_error::UserFacingError;
+/// let err = UserFacingError::new("File failed to open").reason("File not found");
+/// for line in err {
+///     println!("{}", line);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct UserFacingErrorLines {
+    error: UserFacingError,
+    state: LineState,
+    /* Lines of the current section that haven't been returned yet */
+    pending: std::vec::IntoIter<String>,
+}
+
+impl UserFacingErrorLines {
+    // Renders the section the iterator is at, and moves on to the next one
+    fn next_section(&mut self) -> Option<String> {
+        let error = &self.error;
+        let (section, state) = match self.state {
+            LineState::Summary => {
+                let timestamp = error.timestamp_text();
+                let summary = pretty_summary_stamped(
+                    &error.summary,
+                    error.decorations().code.as_deref(),
+                    timestamp.as_deref(),
+                );
+                (Some(summary), LineState::Reason(0))
+            }
+            LineState::Reason(index) => match error.rendered_reasons().as_deref() {
+                /* The reasons left out are counted in place of the first one */
+                Some(reasons) if index == shown_reasons(reasons.len()) && index < reasons.len() => {
+                    (
+                        Some(pretty_more_reasons(reasons.len() - index)),
+                        LineState::Context,
+                    )
+                }
+                Some(reasons) if index < reasons.len() => (
+                    pretty_reason_list(Some(&reasons[index..=index]), &[]),
+                    LineState::Reason(index + 1),
+                ),
+                _ => (None, LineState::Context),
+            },
+            LineState::Context => (error.pretty_context(), LineState::Helptext),
+            LineState::Helptext => (
+                join_sections(
+                    join_sections(
+                        pretty_helptext(
+                            error.helptext.as_ref().map(|helptext| helptext.to_string()),
+                            &[],
+                        ),
+                        error.pretty_command(),
+                    ),
+                    pretty_doc_url(UFE::doc_url(error)),
+                ),
+                LineState::Environment,
+            ),
+            LineState::Environment => (
+                join_sections(pretty_invocation(), error.pretty_environment()),
+                LineState::Footer,
+            ),
+            LineState::Footer => (pretty_footer(UFE::footer(error)), LineState::Done),
+            LineState::Done => return None,
+        };
+        self.state = state;
+        Some(section.unwrap_or_default())
+    }
+}
+
+impl Iterator for UserFacingErrorLines {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            if let Some(line) = self.pending.next() {
+                return Some(line);
+            }
+            let section = terminal::strip_ansi(&self.next_section()?);
+            let lines: Vec<String> = section.lines().map(String::from).collect();
+            self.pending = lines.into_iter();
+        }
+    }
+}
+
+impl IntoIterator for UserFacingError {
+    type Item = String;
+    type IntoIter = UserFacingErrorLines;
+
+    fn into_iter(self) -> UserFacingErrorLines {
+        UserFacingErrorLines {
+            error: self,
+            state: LineState::Summary,
+            pending: Vec::new().into_iter(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_test() {
+        let e = UserFacingError::new("Test Error")
+            .reason("Reason 1")
+            .reason_indented("Reason 2", 1)
+            .help("Try Again")
+            .footer("Report bugs");
+        let lines: Vec<String> = e.into_iter().collect();
+        let expected = vec![
+            "Error: Test Error",
+            " - Reason 1",
+            "   - Reason 2",
+            "Try Again",
+            "Report bugs",
+        ];
+        assert_eq!(lines, expected);
+
+        let lines: Vec<String> = UserFacingError::new("Test Error").into_iter().collect();
+        assert_eq!(lines, vec!["Error: Test Error"]);
+    }
+}
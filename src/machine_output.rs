@@ -0,0 +1,72 @@
+// A machine-readable copy of each printed error, written somewhere other than
+// the human rendering so that tools wrapping the program can parse it.
+
+use crate::UFE;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Where the machine-readable copy of each printed error is written. See
+/// [`set_machine_output()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MachineOutput {
+    /// A file descriptor the program was started with, e.g. 3 when run as
+    /// `program 3>errors.jsonl`
+    #[cfg(unix)]
+    Fd(std::os::unix::io::RawFd),
+    /// A file, which is created if it doesn't exist and appended to if it
+    /// does
+    Path(PathBuf),
+}
+
+// Where the copies are written, if anywhere. Held while writing, so that the
+// copies of errors printed at once don't interleave.
+static TARGET: Mutex<Option<MachineOutput>> = Mutex::new(None);
+
+/// Writes a copy of every error printed from now on to `target`, as its
+/// to_json() rendering on a line of its own, at the same time as the error
+/// is printed. The copy isn't worth failing over, so if the target can't be
+/// written to (e.g. the file descriptor isn't open) it is skipped without a
+/// word.
+/// # Example
+/// ```
+/// use user_error::{set_machine_output, MachineOutput};
+/// set_machine_output(MachineOutput::Path("errors.jsonl".into()));
+/// # user_error::disable_machine_output();
+/// ```
+pub fn set_machine_output(target: MachineOutput) {
+    *TARGET.lock().unwrap_or_else(|e| e.into_inner()) = Some(target);
+}
+
+/// Stops writing a copy of the errors printed, as set with
+/// [`set_machine_output()`].
+/// # Example
+/// ```
+/// user_error::disable_machine_output();
+/// ```
+pub fn disable_machine_output() {
+    *TARGET.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+// Writes a copy of the error to the target, if one is set
+pub(crate) fn write<E: UFE + ?Sized>(error: &E) {
+    let target = TARGET.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(target) = target.as_ref() {
+        let line = error.to_json() + "\n";
+        let _ = append(target, &line);
+    }
+}
+
+// Appends the text to the target in a single write. A file descriptor is
+// opened again through /dev/fd rather than taken over, so that it is left
+// open for the rest of the program.
+fn append(target: &MachineOutput, text: &str) -> io::Result<()> {
+    let path = match target {
+        #[cfg(unix)]
+        MachineOutput::Fd(fd) => PathBuf::from(format!("/dev/fd/{}", fd)),
+        MachineOutput::Path(path) => path.clone(),
+    };
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(text.as_bytes())
+}
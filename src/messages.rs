@@ -0,0 +1,266 @@
+// Every piece of text the crate itself generates is looked up here by a stable
+// identifier. The English strings are the built-in fallback, used whenever no
+// catalog is installed (or the installed catalog lacks a translation).
+
+/// The label printed in front of every summary.
+pub const ERROR_LABEL: &str = "error-label";
+/// Summary of an error that was built without one.
+pub const DEFAULT_SUMMARY: &str = "default-summary";
+/// Notice printed in place of reasons that were left out. Pluralized.
+pub const MORE_REASONS: &str = "more-reasons";
+/// Notice printed after an error that had parts of it left out, saying how
+/// to see them. `{command}` is replaced by the command to run. Pluralized.
+pub const HIDDEN_DETAILS: &str = "hidden-details";
+/// Summary of an error that several errors were collapsed into. Pluralized.
+pub const ERRORS_OCCURRED: &str = "errors-occurred";
+/// Summary of the recap of the errors printed during a run. Pluralized.
+pub const RUN_SUMMARY: &str = "run-summary";
+/// Help text suggesting a single command. `{command}` is replaced by the
+/// command, in backticks.
+pub const SUGGEST_COMMAND: &str = "suggest-command";
+/// Help text suggesting several commands. `{commands}` is replaced by the
+/// commands, each in backticks, separated by commas.
+pub const SUGGEST_COMMANDS: &str = "suggest-commands";
+/// Help text above a command to run, when the error has none of its own.
+pub const TRY_COMMAND: &str = "try-command";
+/// How long ago an error was created, printed after its summary.
+/// `{seconds}` is replaced by the number of seconds, to one decimal place.
+pub const ERROR_AGE: &str = "error-age";
+/// Line offering a link for reporting an error that ends the program.
+/// `{url}` is replaced by the link.
+pub const BUG_REPORT: &str = "bug-report";
+/// Line linking to documentation about an error. `{url}` is replaced by the
+/// link.
+pub const DOC_URL: &str = "doc-url";
+/// Notice printed after an error that a crash report was written for.
+/// `{path}` is replaced by the path of the report.
+pub const CRASH_REPORT: &str = "crash-report";
+
+// The help text and reasons added to IO errors are looked up as "io-help-" and
+// "io-reason-" followed by the kind of error, e.g. "io-help-not-found". The
+// text of errors converted from other errors is looked up by the name of the
+// error followed by the part of it, e.g. "regex-summary" or "semver-help", and
+// that of ready-made errors by what they are for followed by the part, e.g.
+// "env-missing-summary" or "panic-help".
+
+// Placeholder that is replaced by the count in pluralized messages
+pub(crate) const COUNT_PLACEHOLDER: &str = "{count}";
+// Placeholder that is replaced by the command that shows everything
+pub(crate) const COMMAND_PLACEHOLDER: &str = "{command}";
+// Placeholder that is replaced by a list of commands
+pub(crate) const COMMANDS_PLACEHOLDER: &str = "{commands}";
+// Placeholder that is replaced by a number of seconds
+pub(crate) const SECONDS_PLACEHOLDER: &str = "{seconds}";
+// Placeholders that are replaced by the code and message of an OS error
+pub(crate) const CODE_PLACEHOLDER: &str = "{code}";
+pub(crate) const ERROR_PLACEHOLDER: &str = "{error}";
+// Placeholder that is replaced by a link
+pub(crate) const URL_PLACEHOLDER: &str = "{url}";
+// Placeholder that is replaced by the path of a file
+pub(crate) const PATH_PLACEHOLDER: &str = "{path}";
+// Placeholder that is replaced by the name of an environment variable
+pub(crate) const VAR_PLACEHOLDER: &str = "{var}";
+// Placeholders that are replaced by input that could not be parsed, the
+// format it was expected in, and where in it parsing failed
+pub(crate) const INPUT_PLACEHOLDER: &str = "{input}";
+pub(crate) const EXPECTED_PLACEHOLDER: &str = "{expected}";
+pub(crate) const POSITION_PLACEHOLDER: &str = "{position}";
+// Placeholder that is replaced by the name of a host
+pub(crate) const HOST_PLACEHOLDER: &str = "{host}";
+// Placeholder that is replaced by where in the source code a panic happened
+pub(crate) const LOCATION_PLACEHOLDER: &str = "{location}";
+
+// How to get access to a file the user isn't allowed to access
+#[cfg(unix)]
+const PERMISSION_HELP: &str = "Try running with sudo or check file permissions with ls -la";
+#[cfg(target_os = "windows")]
+const PERMISSION_HELP: &str = "Try running as administrator";
+#[cfg(not(any(unix, target_os = "windows")))]
+const PERMISSION_HELP: &str = "Check the file permissions";
+
+/// The built-in English text for an identifier.
+fn english(id: &str) -> &'static str {
+    match id {
+        ERROR_LABEL => "Error:",
+        DEFAULT_SUMMARY => "Something went wrong",
+        SUGGEST_COMMAND => "Try: {command}",
+        SUGGEST_COMMANDS => "Try one of: {commands}",
+        TRY_COMMAND => "Try:",
+        ERROR_AGE => "(occurred {seconds}s ago)",
+        BUG_REPORT => "Report this bug: {url}",
+        DOC_URL => "See: {url}",
+        "io-help-not-found" => "Check the path is correct and the file exists.",
+        "io-help-permission-denied" => "Try running with elevated privileges.",
+        "io-help-already-exists" => "Remove the existing file, or choose a different path.",
+        "io-help-connection-refused" => "Check the server is running and the address is correct.",
+        "io-help-check-network" => "Check your network connection and try again.",
+        "io-help-addr-in-use" => "Stop the program using the address, or choose a different port.",
+        "io-help-storage-full" => "Free up some disk space and try again.",
+        "io-help-read-only-filesystem" => "Choose a path on a writable file system.",
+        "io-reason-unsupported" => "This operation is not supported on this platform",
+        "io-reason-out-of-memory" => "There was not enough memory to complete the operation",
+        "io-reason-storage-full" => "There is no space left on the storage device",
+        "io-reason-quota-exceeded" => "The disk quota for this user was exceeded",
+        "io-reason-read-only-filesystem" => "The file system is mounted read-only",
+        "io-reason-file-too-large" => "The file is larger than the file system allows",
+        "io-reason-not-a-directory" => {
+            "A part of the path is a file, where a directory was expected"
+        }
+        "io-reason-is-a-directory" => "The path is a directory, where a file was expected",
+        "io-reason-directory-not-empty" => "The directory is not empty",
+        "io-reason-crosses-devices" => "Files cannot be moved or linked across file systems",
+        "io-reason-too-many-links" => "The file has too many links to it",
+        "io-reason-resource-busy" => "The file or device is in use by another program",
+        "io-reason-executable-file-busy" => "The program is running, so its file cannot be changed",
+        "io-reason-deadlock" => "The operation would have deadlocked",
+        "io-reason-argument-list-too-long" => "The command line is too long",
+        "io-reason-host-unreachable" => "The host could not be reached",
+        "io-reason-network-unreachable" => "The network could not be reached",
+        "io-reason-network-down" => "The network is down",
+        "io-reason-stale-network-file-handle" => "The file was changed on the network file system",
+        "io-reason-not-seekable" => "The file cannot be read out of order, e.g. it is a pipe",
+        "io-reason-os-error-code" => "OS error code: {code} ({error})",
+        "invalid-char-summary" => "Invalid Unicode character",
+        "invalid-char-reason" => {
+            "The value is not a valid Unicode scalar value \
+             (U+D800 through U+DFFF are surrogates and are not valid)"
+        }
+        "invalid-char-help" => "Ensure the input is valid UTF-32",
+        "regex-summary" => "Invalid regular expression",
+        "regex-help-too-big" => {
+            "Try simplifying the pattern, e.g. by using fewer or smaller repetitions"
+        }
+        "semver-summary" => "Invalid version",
+        "semver-help" => "Versions look like 1.2.3, and version requirements like ^1.0",
+        "task-summary-cancelled" => "Task was cancelled",
+        "task-summary-panicked" => "A background task crashed",
+        "notify-summary-path-not-found" => "Path to watch not found",
+        "notify-reason-path-not-found" => "The path does not exist",
+        "notify-summary-watch-not-found" => "Watch not found",
+        "notify-reason-watch-not-found" => "The path is not being watched",
+        "notify-summary-max-files-watch" => "Too many files watched",
+        "notify-reason-max-files-watch" => "The limit on the number of inotify watches was reached",
+        "notify-help-max-files-watch" => {
+            "Try raising the limit, e.g. with: sudo sysctl fs.inotify.max_user_watches=524288"
+        }
+        "notify-summary-invalid-config" => "Invalid file watcher configuration",
+        "notify-summary-failed" => "Failed to watch for file changes",
+        "env-missing-summary" => "Missing required environment variable",
+        "env-missing-reason" => "'{var}' is not set",
+        "env-missing-help" => "Set it with: export {var}=<value>",
+        "file-not-found-summary" => "File not found",
+        "file-not-found-help" => "Check that the file exists",
+        "permission-denied-summary" => "Permission denied",
+        "permission-denied-reason" => "Cannot access '{path}'",
+        "permission-denied-help" => PERMISSION_HELP,
+        "parse-failure-summary" => "Failed to parse input",
+        "parse-failure-reason" => "Input '{input}' does not match expected format: {expected}",
+        "parse-failure-reason-position" => "At position {position}: '{input}'",
+        "parse-failure-help" => "Check the input format and try again",
+        "network-timeout-summary" => "Network request timed out",
+        "network-timeout-help" => {
+            "Check your internet connection or try increasing the timeout with --timeout"
+        }
+        "panic-summary" => "The application crashed unexpectedly",
+        "panic-reason-location" => "location: {location}",
+        "panic-reason-backtrace" => "backtrace:",
+        "panic-help" => {
+            "This is a bug. Please report it, along with what you were doing when it happened."
+        }
+        CRASH_REPORT => {
+            "A detailed report was written to {path} \u{2014} please attach it when filing a bug."
+        }
+        _ => "",
+    }
+}
+
+/// The built-in English text for a pluralized identifier.
+fn english_plural(id: &str, count: usize) -> &'static str {
+    match (id, count) {
+        (MORE_REASONS, _) => "\u{2026} and {count} more",
+        (HIDDEN_DETAILS, 1) => "1 additional detail hidden; run {command} to see it",
+        (HIDDEN_DETAILS, _) => "{count} additional details hidden; run {command} to see them",
+        (ERRORS_OCCURRED, 1) => "1 error occurred",
+        (ERRORS_OCCURRED, _) => "{count} errors occurred",
+        (RUN_SUMMARY, 1) => "1 error occurred during this run",
+        (RUN_SUMMARY, _) => "{count} errors occurred during this run",
+        ("network-timeout-reason", 1) => "Failed to connect to '{host}' within 1 second",
+        ("network-timeout-reason", _) => "Failed to connect to '{host}' within {count} seconds",
+        ("regex-reason-too-big", 1) => "The compiled pattern exceeds the size limit of 1 byte",
+        ("regex-reason-too-big", _) => {
+            "The compiled pattern exceeds the size limit of {count} bytes"
+        }
+        _ => "",
+    }
+}
+
+/// Returns the text for an identifier, translated if a catalog is installed.
+pub fn message(id: &str) -> String {
+    #[cfg(feature = "i18n")]
+    {
+        if let Some(text) = crate::i18n::lookup(id) {
+            return text;
+        }
+    }
+    english(id).to_string()
+}
+
+/// Returns the text for a pluralized identifier with `{count}` filled in,
+/// translated if a catalog is installed.
+pub fn plural(id: &str, count: usize) -> String {
+    #[cfg(feature = "i18n")]
+    {
+        if let Some(text) = crate::i18n::lookup_plural(id, count) {
+            return text.replace(COUNT_PLACEHOLDER, &count.to_string());
+        }
+    }
+    english_plural(id, count).replace(COUNT_PLACEHOLDER, &count.to_string())
+}
+
+/// Fills in the placeholders in the text in a single pass, so that a value
+/// that happens to contain another placeholder is left as is.
+pub(crate) fn fill(text: &str, values: &[(&str, &str)]) -> String {
+    let mut filled = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match values.iter().find(|(key, _)| rest.starts_with(key)) {
+            Some((key, value)) => {
+                filled.push_str(value);
+                rest = &rest[key.len()..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_fallback_test() {
+        assert_eq!(english(ERROR_LABEL), "Error:");
+        assert_eq!(english_plural(MORE_REASONS, 3), "\u{2026} and {count} more");
+        assert_eq!(english("not-a-message"), "");
+    }
+
+    #[test]
+    fn fill_test() {
+        let values = [
+            (INPUT_PLACEHOLDER, "{expected}"),
+            (EXPECTED_PLACEHOLDER, "HH:MM"),
+        ];
+        assert_eq!(
+            fill("'{input}' is not {expected} {other}", &values),
+            "'{expected}' is not HH:MM {other}"
+        );
+        assert_eq!(fill("no placeholders", &values), "no placeholders");
+    }
+}
@@ -0,0 +1,40 @@
+// Ready-made errors for common network failures, with the context (e.g. the
+// host) that the underlying errors don't carry.
+
+use crate::{messages, UserFacingError};
+use std::convert::TryFrom;
+
+impl UserFacingError {
+    /// An error for a network request that timed out, saying which host
+    /// could not be reached in time.
+    /// # Example
+    /// ```
+    /// # use user_error::UserFacingError;
+    /// let err = UserFacingError::from_network_timeout("example.com", 30);
+    /// ```
+    pub fn from_network_timeout(host: &str, timeout_secs: u64) -> UserFacingError {
+        let seconds = usize::try_from(timeout_secs).unwrap_or(usize::MAX);
+        UserFacingError::new_full(
+            messages::message("network-timeout-summary"),
+            messages::plural("network-timeout-reason", seconds)
+                .replace(messages::HOST_PLACEHOLDER, host),
+            messages::message("network-timeout-help"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{UserFacingError, UFE};
+
+    #[test]
+    fn network_timeout_test() {
+        let ufe = UserFacingError::from_network_timeout("example.com", 30);
+        assert_eq!(ufe.summary(), "Network request timed out");
+        assert_eq!(
+            ufe.reasons().unwrap(),
+            vec!["Failed to connect to 'example.com' within 30 seconds"]
+        );
+        assert!(ufe.helptext().unwrap().contains("--timeout"));
+    }
+}
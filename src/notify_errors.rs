@@ -0,0 +1,85 @@
+// Conversions from the notify crate's errors. Enabled by the "notify" feature.
+
+use std::borrow::Cow;
+
+use crate::{help_from_kind, messages, Reason, UserFacingError};
+use notify::ErrorKind;
+
+/// Allows you to create UserFacingErrors From notify::Errors, so that a file
+/// watcher which fails to start can be reported nicely. Each path the error
+/// is about is listed below the reason.
+/// # Example
+/// ```
+/// use user_error::UserFacingError;
+/// let ufe: UserFacingError = notify::Error::path_not_found().into();
+/// ```
+impl From<notify::Error> for UserFacingError {
+    fn from(error: notify::Error) -> UserFacingError {
+        let lookup = |id| Cow::Owned(messages::message(id));
+        let (summary, reason, helptext) = match &error.kind {
+            ErrorKind::PathNotFound => (
+                "notify-summary-path-not-found",
+                lookup("notify-reason-path-not-found"),
+                None,
+            ),
+            ErrorKind::WatchNotFound => (
+                "notify-summary-watch-not-found",
+                lookup("notify-reason-watch-not-found"),
+                None,
+            ),
+            ErrorKind::MaxFilesWatch => (
+                "notify-summary-max-files-watch",
+                lookup("notify-reason-max-files-watch"),
+                Some(lookup("notify-help-max-files-watch")),
+            ),
+            ErrorKind::InvalidConfig(_) => (
+                "notify-summary-invalid-config",
+                Cow::Owned(error.to_string()),
+                None,
+            ),
+            ErrorKind::Io(io) => (
+                "notify-summary-failed",
+                Cow::Owned(io.to_string()),
+                help_from_kind(io.kind()).map(Cow::Owned),
+            ),
+            ErrorKind::Generic(message) => {
+                ("notify-summary-failed", Cow::Owned(message.clone()), None)
+            }
+        };
+
+        let mut reasons = vec![Reason::from(reason)];
+        reasons.extend(error.paths.iter().map(|path| Reason {
+            level: 1,
+            ..Reason::from(path.display().to_string())
+        }));
+
+        UserFacingError {
+            reasons: Some(reasons),
+            helptext,
+            source: Some(Box::new(error)),
+            ..UserFacingError::new(messages::message(summary))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{UserFacingError, UFE};
+    use std::path::PathBuf;
+
+    #[test]
+    fn max_files_watch_test() {
+        let error = notify::Error::new(notify::ErrorKind::MaxFilesWatch)
+            .add_path(PathBuf::from("/srv/data"));
+        let ufe = UserFacingError::from(error);
+        assert_eq!(ufe.summary(), "Too many files watched");
+        let reasons = ufe.reasons().unwrap();
+        assert!(reasons[0].contains("inotify watches"));
+        assert_eq!(reasons[1], "/srv/data");
+        assert!(ufe
+            .helptext()
+            .unwrap()
+            .contains("fs.inotify.max_user_watches"));
+        assert!(ufe.source.is_some());
+    }
+}
@@ -0,0 +1,480 @@
+// Where errors are printed.
+
+use crate::config;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// The standard stream an error is printed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Stream {
+    /// Standard output
+    Stdout,
+    /// Standard error (the default)
+    #[default]
+    Stderr,
+}
+
+impl Stream {
+    // Prints already formatted text to the stream. Failing to print an error
+    // is not worth panicking over, so any failure is ignored.
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    pub(crate) fn print(self, text: &str) {
+        let _ = match self {
+            Stream::Stdout => self.write(text, &mut io::stdout().lock(), &mut io::sink()),
+            Stream::Stderr => self.write(text, &mut io::sink(), &mut io::stderr().lock()),
+        };
+    }
+
+    // Prints already formatted text to the stream, through the pager if it is
+    // long enough to be paged. Falls back to printing it directly if the
+    // pager could not be started.
+    pub(crate) fn print_paged(self, text: &str) {
+        let threshold = config::read(|config| config.page_threshold);
+        let paged = is_long(text, threshold) && self.is_terminal();
+        page_or_print(
+            text,
+            paged,
+            |text| spawn_pager(text, self.into()),
+            |text| self.print(text),
+        );
+    }
+
+    // In the browser there are no standard streams, so the text is printed to
+    // the console as an error, without any colors
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    pub(crate) fn print(self, text: &str) {
+        let text = crate::terminal::strip_ansi(text);
+        web_sys::console::error_1(&wasm_bindgen::JsValue::from_str(&text));
+    }
+
+    // Writes text to whichever of the two writers corresponds to the stream.
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn write<'a>(
+        self,
+        text: &str,
+        stdout: &'a mut dyn Write,
+        stderr: &'a mut dyn Write,
+    ) -> io::Result<()> {
+        let writer = match self {
+            Stream::Stdout => stdout,
+            Stream::Stderr => stderr,
+        };
+        writer.write_all(text.as_bytes())?;
+        writer.flush()
+    }
+}
+
+// The writer shared by every error printed with print_to_stderr_buffered(), so
+// that errors printed by different threads at once don't interleave
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+static BUFFERED_STDERR: OnceLock<std::sync::Mutex<io::BufWriter<io::Stderr>>> = OnceLock::new();
+
+// Prints already formatted text to standard error in one piece, holding the
+// shared writer until it is flushed. Any failure is ignored.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) fn print_buffered(text: &str) {
+    let writer =
+        BUFFERED_STDERR.get_or_init(|| std::sync::Mutex::new(io::BufWriter::new(io::stderr())));
+    let _ = write_exclusively(writer, text);
+}
+
+// The browser's console is already written to one message at a time
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) fn print_buffered(text: &str) {
+    Stream::Stderr.print(text);
+}
+
+// Writes and flushes all of the text while holding the lock on the writer. A
+// poisoned lock is used as is, since the writer is still usable.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn write_exclusively<W: Write>(writer: &std::sync::Mutex<W>, text: &str) -> io::Result<()> {
+    let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+    writer.write_all(text.as_bytes())?;
+    writer.flush()
+}
+
+// The pager writes to the same stream the error would have been printed to
+impl From<Stream> for Stdio {
+    fn from(stream: Stream) -> Stdio {
+        match stream {
+            Stream::Stdout => Stdio::inherit(),
+            Stream::Stderr => Stdio::from(io::stderr()),
+        }
+    }
+}
+
+impl Stream {
+    // Whether the stream is connected to a terminal. The browser's console is
+    // never treated as one.
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn is_terminal(self) -> bool {
+        match self {
+            Stream::Stdout => io::stdout().is_terminal(),
+            Stream::Stderr => io::stderr().is_terminal(),
+        }
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    fn is_terminal(self) -> bool {
+        false
+    }
+}
+
+/// How errors are rendered when they are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputStyle {
+    /// Colored if the stream is a terminal and NO_COLOR is not set, plain
+    /// otherwise
+    Auto,
+    /// Without any color
+    Plain,
+    /// Always colored with ANSI escape codes (the default)
+    #[default]
+    Ansi,
+    /// As a single line of JSON
+    Json,
+}
+
+impl OutputStyle {
+    /// Parses the value of the USER_ERROR_STYLE environment variable:
+    /// "plain", "ansi", "json" or "auto". Any other value is Auto, and an
+    /// unset variable is the default, Ansi.
+    pub fn from_env_value(value: Option<&str>) -> OutputStyle {
+        match value
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            None => OutputStyle::Ansi,
+            Some("plain") => OutputStyle::Plain,
+            Some("ansi") => OutputStyle::Ansi,
+            Some("json") => OutputStyle::Json,
+            _ => OutputStyle::Auto,
+        }
+    }
+
+    // Resolves Auto into Plain or Ansi, given whether the stream is a terminal
+    // and the value of NO_COLOR. Any other style is unchanged.
+    fn resolve(self, is_terminal: bool, no_color: Option<&str>) -> OutputStyle {
+        match self {
+            OutputStyle::Auto => {
+                let no_color = no_color.is_some_and(|value| !value.is_empty());
+                if !no_color && is_terminal {
+                    OutputStyle::Ansi
+                } else {
+                    OutputStyle::Plain
+                }
+            }
+            style => style,
+        }
+    }
+}
+
+// The output style read from the USER_ERROR_STYLE environment variable (read
+// at most once)
+static ENV_STYLE: OnceLock<OutputStyle> = OnceLock::new();
+
+/// Sets how errors are rendered when they are printed, overriding the
+/// USER_ERROR_STYLE environment variable. Errors are colored by default; use
+/// OutputStyle::Auto to leave the colors out when they aren't printed to a
+/// terminal or NO_COLOR is set.
+/// # Example
+/// ```
+/// use user_error::{set_output_style, OutputStyle};
+/// set_output_style(OutputStyle::Plain);
+/// ```
+pub fn set_output_style(style: OutputStyle) {
+    config::update(|config| config.style = Some(style));
+}
+
+/// Returns how errors are rendered when they are printed: the style set with
+/// [`set_output_style()`], or else the one chosen by the USER_ERROR_STYLE
+/// environment variable ("plain", "ansi", "json" or "auto").
+pub fn output_style() -> OutputStyle {
+    config::read(|config| config.style)
+        .unwrap_or_else(|| *ENV_STYLE.get_or_init(|| style_from_env(|key| std::env::var(key).ok())))
+}
+
+// Reads the output style from the environment
+fn style_from_env(var: impl Fn(&str) -> Option<String>) -> OutputStyle {
+    OutputStyle::from_env_value(var("USER_ERROR_STYLE").as_deref())
+}
+
+// Renders an error for the stream in the output style: the pretty (colored)
+// rendering, with the colors removed if need be, or its JSON rendering.
+pub(crate) fn styled(stream: Stream, pretty: &str, json: impl FnOnce() -> String) -> String {
+    let no_color = std::env::var("NO_COLOR").ok();
+    match output_style().resolve(stream.is_terminal(), no_color.as_deref()) {
+        OutputStyle::Json => [&json(), "\n"].concat(),
+        OutputStyle::Plain => crate::terminal::strip_ansi(pretty),
+        _ => pretty.to_string(),
+    }
+}
+
+/// Sets the stream errors are printed to, unless a different one is chosen
+/// for a particular error.
+/// # Example
+/// ```
+/// use user_error::{set_default_stream, Stream};
+/// set_default_stream(Stream::Stdout);
+/// ```
+pub fn set_default_stream(stream: Stream) {
+    config::update(|config| config.stream = stream);
+}
+
+/// Returns the stream errors are printed to, unless a different one is chosen
+/// for a particular error.
+pub fn default_stream() -> Stream {
+    config::read(|config| config.stream)
+}
+
+/// Shows printed errors that are longer than the given number of lines
+/// through the user's pager (the PAGER environment variable, or else
+/// `less -R`), as long as they are printed to a terminal. Errors are not
+/// paged by default.
+/// # Example
+/// ```
+/// user_error::page_long_output(40);
+/// ```
+pub fn page_long_output(threshold_lines: usize) {
+    config::update(|config| config.page_threshold = Some(threshold_lines));
+}
+
+// Whether the text has more lines than the paging threshold (if any)
+fn is_long(text: &str, threshold: Option<usize>) -> bool {
+    threshold.is_some_and(|threshold| text.lines().count() > threshold)
+}
+
+// Pages the text if it should be, or else prints it. Also prints it if the
+// pager fails.
+fn page_or_print(
+    text: &str,
+    paged: bool,
+    page: impl FnOnce(&str) -> io::Result<()>,
+    print: impl FnOnce(&str),
+) {
+    if !paged || page(text).is_err() {
+        print(text);
+    }
+}
+
+// The pager to run: its program, its arguments, and whether it displays ANSI
+// escape codes. The pager is PAGER if set, or else less. If the pager is less
+// it is told to display colors (-R), unless LESS already does that.
+fn pager_command(pager: Option<&str>, less: Option<&str>) -> (String, Vec<String>, bool) {
+    let pager = pager
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or("less");
+    let mut words = pager.split_whitespace().map(String::from);
+    let program = words.next().unwrap_or_default();
+    let mut args: Vec<String> = words.collect();
+
+    let is_less = Path::new(&program).file_stem() == Some("less".as_ref());
+    let mut options = less.into_iter().chain(args.iter().map(String::as_str));
+    if is_less && !options.any(less_shows_colors) {
+        args.push("-R".to_string());
+    }
+    (program, args, is_less)
+}
+
+// Whether less options such as "-FRX" include -R (display colors)
+fn less_shows_colors(options: &str) -> bool {
+    options
+        .split_whitespace()
+        .any(|option| match option.strip_prefix("--") {
+            Some(long) => long.eq_ignore_ascii_case("RAW-CONTROL-CHARS"),
+            None => option.contains('R'),
+        })
+}
+
+// Pipes the text through the user's pager and waits for it to exit. Fails if
+// stdout is not a terminal, or the pager could not be started.
+pub(crate) fn page(text: &str) -> io::Result<()> {
+    if !io::stdout().is_terminal() {
+        return Err(io::Error::other("stdout is not a terminal"));
+    }
+    spawn_pager(text, Stdio::inherit())
+}
+
+// Pipes the text through the user's pager, which writes to the given output,
+// and waits for it to exit. Fails if the pager could not be started.
+fn spawn_pager(text: &str, output: Stdio) -> io::Result<()> {
+    let pager = std::env::var("PAGER").ok();
+    let less = std::env::var("LESS").ok();
+    let (program, args, shows_colors) = pager_command(pager.as_deref(), less.as_deref());
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(output)
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let text = if shows_colors {
+            text.to_string()
+        } else {
+            crate::terminal::strip_ansi(text)
+        };
+        /* The user may quit the pager before reading everything */
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn write_to_stream_test() {
+        for stream in [Stream::Stdout, Stream::Stderr] {
+            let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+            stream.write("Error", &mut stdout, &mut stderr).unwrap();
+
+            let (chosen, other) = match stream {
+                Stream::Stdout => (stdout, stderr),
+                Stream::Stderr => (stderr, stdout),
+            };
+            assert_eq!(chosen, b"Error");
+            assert!(other.is_empty());
+        }
+    }
+
+    // Writes a byte at a time into the shared buffer, giving other threads
+    // every chance to write in between
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    struct Trickle(Arc<Mutex<Vec<u8>>>);
+
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            std::thread::yield_now();
+            self.0
+                .lock()
+                .unwrap()
+                .extend_from_slice(&buf[..1.min(buf.len())]);
+            Ok(1.min(buf.len()))
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn write_exclusively_test() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let writer = Arc::new(Mutex::new(Trickle(written.clone())));
+        let threads: Vec<_> = (0..8)
+            .map(|thread| {
+                let writer = writer.clone();
+                std::thread::spawn(move || {
+                    let text = format!("Error: thread {} failed\n", thread);
+                    for _ in 0..20 {
+                        write_exclusively(&writer, &text).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        /* Every line was written whole */
+        let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+        assert_eq!(written.lines().count(), 160);
+        for line in written.lines() {
+            assert!(line.starts_with("Error: thread ") && line.ends_with(" failed"));
+            assert_eq!(line.len(), "Error: thread 0 failed".len());
+        }
+    }
+
+    #[test]
+    fn pager_command_test() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            pager_command(None, None),
+            ("less".into(), args(&["-R"]), true)
+        );
+        assert_eq!(
+            pager_command(Some(" "), Some("FRX")),
+            ("less".into(), args(&[]), true)
+        );
+        assert_eq!(
+            pager_command(Some("/usr/bin/less -F"), Some("-X")),
+            ("/usr/bin/less".into(), args(&["-F", "-R"]), true)
+        );
+        assert_eq!(
+            pager_command(Some("less -SR"), None),
+            ("less".into(), args(&["-SR"]), true)
+        );
+        assert_eq!(
+            pager_command(Some("more"), Some("R")),
+            ("more".into(), args(&[]), false)
+        );
+    }
+
+    #[test]
+    fn is_long_test() {
+        let text = "Error: Failed\n - One\n - Two\n";
+        assert!(is_long(text, Some(2)));
+        assert!(!is_long(text, Some(3)));
+        assert!(!is_long(text, None));
+    }
+
+    #[test]
+    fn page_or_print_test() {
+        use std::cell::RefCell;
+
+        let printed = RefCell::new(Vec::new());
+        let print = |text: &str| printed.borrow_mut().push(text.to_string());
+
+        /* Not paged */
+        page_or_print("short", false, |_| panic!("paged"), print);
+        /* Paged */
+        page_or_print("long", true, |_| Ok(()), print);
+        /* The pager could not be started */
+        let fail = |_: &str| Err(io::Error::other("no pager"));
+        page_or_print("fallback", true, fail, print);
+
+        assert_eq!(*printed.borrow(), vec!["short", "fallback"]);
+    }
+
+    #[test]
+    fn style_from_env_test() {
+        let env = |value: &'static str| {
+            move |key: &str| match key {
+                "USER_ERROR_STYLE" => Some(value.to_string()),
+                _ => None,
+            }
+        };
+        assert_eq!(style_from_env(env("plain")), OutputStyle::Plain);
+        assert_eq!(style_from_env(env("ansi")), OutputStyle::Ansi);
+        assert_eq!(style_from_env(env(" JSON ")), OutputStyle::Json);
+        assert_eq!(style_from_env(env("auto")), OutputStyle::Auto);
+        assert_eq!(style_from_env(env("sparkly")), OutputStyle::Auto);
+        assert_eq!(style_from_env(|_: &str| None), OutputStyle::Ansi);
+    }
+
+    #[test]
+    fn resolve_style_test() {
+        use OutputStyle::*;
+        assert_eq!(Auto.resolve(true, None), Ansi);
+        assert_eq!(Auto.resolve(true, Some("")), Ansi);
+        assert_eq!(Auto.resolve(true, Some("1")), Plain);
+        assert_eq!(Auto.resolve(false, None), Plain);
+        assert_eq!(Plain.resolve(true, None), Plain);
+        assert_eq!(Ansi.resolve(false, Some("1")), Ansi);
+        assert_eq!(Json.resolve(true, None), Json);
+    }
+
+    #[test]
+    fn default_stream_test() {
+        assert_eq!(Stream::default(), Stream::Stderr);
+    }
+}
@@ -0,0 +1,81 @@
+// Printing panics the same way as errors, instead of the default message.
+
+use crate::{messages, Stream, UserFacingError, UFE};
+use std::backtrace::{Backtrace, BacktraceStatus};
+
+/// Replaces the panic hook with one that prints panics as errors: the panic
+/// message and where it happened are the reasons, and the help text asks the
+/// user to report the bug. A backtrace is included if RUST_BACKTRACE (or
+/// RUST_LIB_BACKTRACE) asks for one. Panics are always printed to stderr.
+/// # Example
+/// ```no_run
+/// user_error::install_panic_hook();
+/// panic!("Database is locked");
+/// ```
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let location = info.location().map(|location| location.to_string());
+        let backtrace = Backtrace::capture();
+        let backtrace = match backtrace.status() {
+            BacktraceStatus::Captured => Some(backtrace.to_string()),
+            _ => None,
+        };
+        crash_report(message, location, backtrace).print();
+    }));
+}
+
+// The error printed in place of a panic
+fn crash_report(
+    message: String,
+    location: Option<String>,
+    backtrace: Option<String>,
+) -> UserFacingError {
+    let mut error = UserFacingError::new(messages::message("panic-summary"))
+        .reason(message)
+        .help(messages::message("panic-help"))
+        .on_stream(Stream::Stderr);
+    if let Some(location) = location {
+        error = error.reason(
+            messages::message("panic-reason-location")
+                .replace(messages::LOCATION_PLACEHOLDER, &location),
+        );
+    }
+    if let Some(backtrace) = backtrace {
+        error = error.reason(messages::message("panic-reason-backtrace"));
+        for line in backtrace.lines().filter(|line| !line.trim().is_empty()) {
+            error = error.reason_indented(line.trim().to_string(), 1);
+        }
+    }
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crash_report_test() {
+        let backtrace = "0: main::run\n   at src/main.rs:3:5\n".to_string();
+        let report = crash_report(
+            "Database is locked".to_string(),
+            Some("src/main.rs:3:5".to_string()),
+            Some(backtrace),
+        );
+        assert_eq!(report.summary(), "The application crashed unexpectedly");
+        let expected = vec![
+            "Database is locked",
+            "location: src/main.rs:3:5",
+            "backtrace:",
+            "0: main::run",
+            "at src/main.rs:3:5",
+        ];
+        assert_eq!(report.reasons().unwrap(), expected);
+        assert!(report.helptext().unwrap().contains("report it"));
+    }
+}
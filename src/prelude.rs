@@ -0,0 +1,22 @@
+//! # Prelude
+//! Everything needed to build and print errors, in one import. Only adds
+//! names, so it can be used alongside explicit imports from the crate root.
+//!
+//! # Example
+//! ```
+//! use user_error::prelude::*;
+//!
+//! fn open() -> Result<(), UserFacingError> {
+//!     let io = std::io::Error::new(std::io::ErrorKind::NotFound, "config.toml");
+//!     Err(UserFacingError::from(io).reason("Config file is missing"))
+//! }
+//!
+//! if let Err(err) = open() {
+//!     let _ = err.to_pretty_string();
+//! }
+//! ```
+
+pub use crate::{AttachPending, IntoUserFacing, UFEConsts, UserFacingError, UFE};
+
+#[cfg(feature = "derive")]
+pub use crate::user_error;
@@ -0,0 +1,100 @@
+// Hiding secrets (e.g. passwords and tokens) in errors when they are
+// rendered. The errors themselves are left as they are.
+
+use std::borrow::Cow;
+use std::sync::RwLock;
+
+// What a secret is replaced with
+const REDACTED: &str = "[redacted]";
+
+/// A function that hides secrets in a piece of text.
+type Redactor = fn(&str) -> String;
+
+// The values that what follows is hidden after, and the function every piece
+// of text is passed through
+static VALUES: RwLock<Vec<String>> = RwLock::new(Vec::new());
+static REDACTOR: RwLock<Option<Redactor>> = RwLock::new(None);
+
+/// Replaces what follows each of the values (e.g. "password=" or "token ")
+/// in text, up to the next whitespace, with "[redacted]" when errors are
+/// rendered. The values themselves are kept, so that the user can tell what
+/// was hidden. Replaces any values set before.
+/// # Example
+/// ```
+/// user_error::redact_values(&["password=", "token "]);
+/// ```
+pub fn redact_values(values: &[&str]) {
+    let mut redacted = VALUES.write().unwrap_or_else(|e| e.into_inner());
+    *redacted = values.iter().map(|value| value.to_string()).collect();
+}
+
+/// Sets a function that every piece of text is passed through when errors
+/// are rendered, to hide secrets that redact_values() can't find. It is
+/// applied after the values are redacted.
+/// # Example
+/// ```
+/// fn hide_home(text: &str) -> String {
+///     text.replace("/home/amy", "~")
+/// }
+/// user_error::set_redactor(hide_home);
+/// ```
+pub fn set_redactor(redactor: fn(&str) -> String) {
+    let mut current = REDACTOR.write().unwrap_or_else(|e| e.into_inner());
+    *current = Some(redactor);
+}
+
+/// Hides the secrets in text that is about to be rendered, as configured.
+pub(crate) fn redact(text: &str) -> Cow<'_, str> {
+    let mut text = Cow::Borrowed(text);
+    {
+        let values = VALUES.read().unwrap_or_else(|e| e.into_inner());
+        for value in values.iter().filter(|value| !value.is_empty()) {
+            if text.contains(value.as_str()) {
+                text = Cow::Owned(redact_value(&text, value));
+            }
+        }
+    }
+    /* Not called with the lock held, in case it renders an error itself */
+    let redactor = *REDACTOR.read().unwrap_or_else(|e| e.into_inner());
+    match redactor {
+        Some(redactor) => Cow::Owned(redactor(&text)),
+        None => text,
+    }
+}
+
+// Replaces what follows each occurrence of the value, up to the next
+// whitespace, with REDACTED
+fn redact_value(text: &str, value: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(value) {
+        let (before, after) = rest.split_at(start + value.len());
+        redacted.push_str(before);
+        let secret = after.find(char::is_whitespace).unwrap_or(after.len());
+        if secret > 0 {
+            redacted.push_str(REDACTED);
+        }
+        rest = &after[secret..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact_value_test() {
+        assert_eq!(
+            redact_value("postgres://db?password=hunter2 failed", "password="),
+            "postgres://db?password=[redacted] failed"
+        );
+        assert_eq!(
+            redact_value("token abc and token def", "token "),
+            "token [redacted] and token [redacted]"
+        );
+        assert_eq!(redact_value("password=", "password="), "password=");
+        assert_eq!(redact_value("no secrets", "password="), "no secrets");
+    }
+}
@@ -0,0 +1,62 @@
+// Conversions from the regex crate's errors. Enabled by the "regex" feature.
+
+use std::borrow::Cow;
+
+use crate::{messages, Reason, UserFacingError};
+
+/// Allows you to create UserFacingErrors From regex::Errors, so that a user
+/// supplied pattern which fails to compile can be reported nicely. The syntax
+/// error (which points at the offending part of the pattern) is the reason.
+/// # Example
+/// ```
+/// use user_error::UserFacingError;
+/// let ufe: UserFacingError = regex::Regex::new("a(").unwrap_err().into();
+/// ```
+impl From<regex::Error> for UserFacingError {
+    fn from(error: regex::Error) -> UserFacingError {
+        let (reason, helptext) = match &error {
+            regex::Error::Syntax(message) => (message.clone(), None),
+            regex::Error::CompiledTooBig(limit) => (
+                messages::plural("regex-reason-too-big", *limit),
+                Some(Cow::Owned(messages::message("regex-help-too-big"))),
+            ),
+            _ => (error.to_string(), None),
+        };
+
+        UserFacingError {
+            reasons: Some(vec![Reason::from(reason)]),
+            helptext,
+            source: Some(Box::new(error)),
+            ..UserFacingError::new(messages::message("regex-summary"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{UserFacingError, UFE};
+
+    #[test]
+    #[allow(clippy::invalid_regex)]
+    fn syntax_error_test() {
+        let error = regex::Regex::new("a(").unwrap_err();
+        let ufe = UserFacingError::from(error);
+        assert_eq!(ufe.summary(), "Invalid regular expression");
+        let reasons = ufe.reasons().unwrap();
+        assert_eq!(reasons.len(), 1);
+        assert!(reasons[0].contains("unclosed group"));
+        assert!(ufe.helptext().is_none());
+        assert!(ufe.source.is_some());
+    }
+
+    #[test]
+    fn compiled_too_big_test() {
+        let error = regex::RegexBuilder::new("\\w{1000}")
+            .size_limit(1)
+            .build()
+            .unwrap_err();
+        let ufe = UserFacingError::from(error);
+        assert!(ufe.reasons().unwrap()[0].contains("size limit"));
+        assert!(ufe.helptext().unwrap().contains("simplifying"));
+    }
+}
@@ -0,0 +1,104 @@
+// Mirroring the errors that end the program into an error tracker, through a
+// hook that integrations install.
+
+use crate::UFE;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, RwLock};
+
+/// A function that is handed each error that ends the program, e.g. to send
+/// it to an error tracker.
+pub type ReportHook = Box<dyn Fn(&dyn UFE) + Send + Sync>;
+
+// The installed hook (if any). It is shared so that it can be called without
+// holding the lock, letting it replace or remove itself.
+static HOOK: RwLock<Option<Arc<ReportHook>>> = RwLock::new(None);
+
+/// Installs a hook that print_and_exit() hands the error to before printing
+/// it, replacing the previous one (if any). The error is converted into a
+/// UserFacingError first, so that errors of unsized types can be handed over
+/// too; this only happens when a hook is installed. If the hook panics, the
+/// panic is caught (after the panic hook has printed it) and the error is
+/// printed as usual.
+/// # Example
+/// ```
+/// user_error::set_report_hook(Box::new(|error| {
+///     let _ = error.to_string();
+/// }));
+/// ```
+pub fn set_report_hook(hook: ReportHook) {
+    let mut current = HOOK.write().unwrap_or_else(|e| e.into_inner());
+    *current = Some(Arc::new(hook));
+}
+
+/// Removes the installed report hook.
+/// # Example
+/// ```
+/// user_error::clear_report_hook();
+/// ```
+pub fn clear_report_hook() {
+    let mut current = HOOK.write().unwrap_or_else(|e| e.into_inner());
+    *current = None;
+}
+
+// Hands the error to the installed hook, if any, ignoring any panic in it
+pub(crate) fn report<E: UFE + ?Sized>(error: &E) {
+    let hook = HOOK.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(hook) = hook {
+        let error = error.into_ufe();
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(&error)));
+    }
+}
+
+/// A report hook that sends each error to Sentry as a fatal event, through
+/// the client bound to the current Sentry hub. The message is the error
+/// without any styling: its summary, reasons and help text.
+/// # Example
+/// ```
+/// user_error::set_report_hook(user_error::sentry_report_hook());
+/// ```
+#[cfg(feature = "sentry")]
+pub fn sentry_report_hook() -> ReportHook {
+    Box::new(|error| {
+        sentry_core::capture_message(&error.render_plain(), sentry_core::Level::Fatal);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UserFacingError;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn report_hook_test() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        set_report_hook(Box::new(move |error| {
+            let reasons = error.reasons().unwrap_or_default();
+            recorder.lock().unwrap().push((error.summary(), reasons));
+        }));
+        report(&UserFacingError::new("Failed to sync").reason("Timed out"));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![("Failed to sync".to_string(), vec!["Timed out".to_string()])]
+        );
+
+        /* A hook that panics is ignored */
+        set_report_hook(Box::new(|_| panic!("tracker is down")));
+        report(&UserFacingError::new("Failed to sync"));
+
+        clear_report_hook();
+        report(&UserFacingError::new("Failed to sync"));
+        assert_eq!(seen.lock().unwrap().len(), 1);
+
+        /* A hook can remove itself */
+        let recorder = seen.clone();
+        set_report_hook(Box::new(move |error| {
+            clear_report_hook();
+            recorder.lock().unwrap().push((error.summary(), Vec::new()));
+        }));
+        report(&UserFacingError::new("Failed to sync"));
+        report(&UserFacingError::new("Failed to sync"));
+        assert_eq!(seen.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,204 @@
+// Ambient context that errors created within a scope are enriched with, e.g.
+// which item of a batch was being processed at the time, and summaries that
+// errors escaping a scope are wrapped with.
+
+use crate::UserFacingError;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+thread_local! {
+    // The labels of the scopes entered on this thread, outermost first
+    static SCOPES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    // The pending summaries of the scopes entered on this thread, outermost
+    // first
+    static PENDING: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Keeps a context scope entered until it is dropped. Created with
+/// [`context_scope()`].
+#[derive(Debug)]
+#[must_use = "the scope is left as soon as the guard is dropped"]
+pub struct ContextGuard {
+    /* How many scopes were entered before this one */
+    depth: usize,
+    /* Scopes belong to a thread, so the guard can't be sent to another */
+    thread_bound: PhantomData<*const ()>,
+}
+
+/// Enters a context scope on this thread: every UserFacingError created
+/// until the returned guard is dropped gets the label as a reason, after its
+/// own reasons. Labels of nested scopes are listed outermost first.
+/// # Example
+/// ```
+/// use user_error::{context_scope, UserFacingError, UFE};
+/// let _repo = context_scope("while processing repo user-error");
+/// let _branch = context_scope("on branch main");
+/// let err = UserFacingError::new("Failed to fetch");
+/// assert_eq!(
+///     err.reasons().unwrap(),
+///     vec!["while processing repo user-error", "on branch main"]
+/// );
+/// ```
+pub fn context_scope<S: Into<String>>(label: S) -> ContextGuard {
+    let depth = SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        scopes.push(label.into());
+        scopes.len() - 1
+    });
+    ContextGuard {
+        depth,
+        thread_bound: PhantomData,
+    }
+}
+
+impl Drop for ContextGuard {
+    // Leaves this scope, along with any scopes nested in it
+    fn drop(&mut self) {
+        let _ = SCOPES.try_with(|scopes| scopes.borrow_mut().truncate(self.depth));
+    }
+}
+
+// The labels of the scopes currently entered on this thread, outermost first
+pub(crate) fn labels() -> Vec<String> {
+    SCOPES
+        .try_with(|scopes| scopes.borrow().clone())
+        .unwrap_or_default()
+}
+
+/// Keeps a pending summary active until it is dropped. Created with
+/// [`err_context()`].
+#[derive(Debug)]
+#[must_use = "the summary stops being pending as soon as the guard is dropped"]
+pub struct PendingGuard {
+    /* How many summaries were pending before this one */
+    depth: usize,
+    /* Summaries belong to a thread, so the guard can't be sent to another */
+    thread_bound: PhantomData<*const ()>,
+}
+
+/// Makes the summary pending on this thread until the returned guard is
+/// dropped. An error passed through [`wrap_pending()`] (or
+/// [`AttachPending::attach_pending()`]) meanwhile is wrapped with it, as
+/// with [`UserFacingError::wrap_with()`]: the summary replaces the error's,
+/// which becomes a reason.
+/// # Example
+/// ```
+/// use user_error::{err_context, wrap_pending, UserFacingError, UFE};
+/// let _g = err_context("Failed to synchronize profiles");
+/// let err = wrap_pending(UserFacingError::new("Profile not found"));
+/// assert_eq!(err.summary(), "Failed to synchronize profiles");
+/// assert_eq!(err.reasons().unwrap(), vec!["Profile not found"]);
+/// ```
+pub fn err_context<S: Into<String>>(summary: S) -> PendingGuard {
+    let depth = PENDING.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        pending.push(summary.into());
+        pending.len() - 1
+    });
+    PendingGuard {
+        depth,
+        thread_bound: PhantomData,
+    }
+}
+
+impl Drop for PendingGuard {
+    // Drops this summary, along with any pending since
+    fn drop(&mut self) {
+        let _ = PENDING.try_with(|pending| pending.borrow_mut().truncate(self.depth));
+    }
+}
+
+/// Wraps the error with every summary pending on this thread, innermost
+/// first: the outermost becomes the summary, and the others and then the
+/// error's own summary become its first reasons.
+/// # Example
+/// ```
+/// use user_error::{err_context, wrap_pending, UserFacingError, UFE};
+/// let _outer = err_context("Failed to synchronize profiles");
+/// let _inner = err_context("Failed to load profile 'work'");
+/// let err = wrap_pending(UserFacingError::new("File not found"));
+/// assert_eq!(
+///     err.reasons().unwrap(),
+///     vec!["Failed to load profile 'work'", "File not found"]
+/// );
+/// ```
+pub fn wrap_pending(error: UserFacingError) -> UserFacingError {
+    let pending = PENDING
+        .try_with(|pending| pending.borrow().clone())
+        .unwrap_or_default();
+    pending
+        .into_iter()
+        .rev()
+        .fold(error, UserFacingError::wrap_with)
+}
+
+/// Wraps the error of a Result with the summaries pending on this thread,
+/// see [`wrap_pending()`].
+/// # Example
+/// ```
+/// use user_error::{err_context, AttachPending, UserFacingError};
+/// fn sync() -> Result<(), UserFacingError> {
+///     let _g = err_context("Failed to synchronize profiles");
+///     Err(UserFacingError::new("Profile not found")).attach_pending()
+/// }
+/// ```
+pub trait AttachPending<T> {
+    /// Wraps the error, if any, with every pending summary.
+    fn attach_pending(self) -> Result<T, UserFacingError>;
+}
+
+impl<T> AttachPending<T> for Result<T, UserFacingError> {
+    fn attach_pending(self) -> Result<T, UserFacingError> {
+        self.map_err(wrap_pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context_scope, UFE};
+
+    #[test]
+    fn context_scope_test() {
+        let repo = context_scope("while processing repo X");
+        {
+            let _branch = context_scope("on branch Y");
+            let e = UserFacingError::new("Failed to fetch").reason("Connection reset");
+            assert_eq!(
+                e.reasons().unwrap(),
+                vec!["Connection reset", "while processing repo X", "on branch Y"]
+            );
+            let (_, reasons, _) = e.into_parts();
+            assert_eq!(reasons.len(), 3);
+        }
+        let e = UserFacingError::new("Failed to fetch");
+        assert_eq!(e.reasons().unwrap(), vec!["while processing repo X"]);
+        assert!(e.to_string().contains("while processing repo X"));
+
+        drop(repo);
+        assert!(UserFacingError::new("Failed to fetch").reasons().is_none());
+    }
+
+    #[test]
+    fn attach_pending_test() {
+        fn load() -> Result<(), UserFacingError> {
+            let _g = err_context("Failed to load profile");
+            Err(UserFacingError::new("File not found")).attach_pending()
+        }
+        fn sync() -> Result<(), UserFacingError> {
+            let _g = err_context("Failed to synchronize profiles");
+            load()
+        }
+
+        let e = sync().unwrap_err();
+        assert_eq!(e.summary(), "Failed to synchronize profiles");
+        assert_eq!(
+            e.reasons().unwrap(),
+            vec!["Failed to load profile", "File not found"]
+        );
+
+        /* Nothing is pending once the guards are dropped */
+        let e = Err::<(), _>(UserFacingError::new("File not found")).attach_pending();
+        assert_eq!(e.unwrap_err().summary(), "File not found");
+    }
+}
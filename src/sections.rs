@@ -0,0 +1,184 @@
+// Displaying each section of an error on its own, e.g. to embed it in a
+// larger message.
+
+use crate::redact::redact;
+use crate::{error_sources, pretty_helptext, pretty_reasons, pretty_summary, UFE};
+use std::fmt::{self, Display};
+
+/// The formatted summary of an error on its own, with colors but without a
+/// trailing newline. Created with
+/// [`UFE::summary_as_display()`](crate::UFE::summary_as_display).
+#[derive(Debug)]
+pub struct SummaryDisplay<'a, E: UFE + ?Sized> {
+    error: &'a E,
+}
+
+/// The formatted reasons of an error on their own, with colors but without a
+/// trailing newline. Displays as nothing if there are no reasons. Created
+/// with [`UFE::reasons_as_display()`](crate::UFE::reasons_as_display).
+#[derive(Debug)]
+pub struct ReasonsDisplay<'a, E: UFE + ?Sized> {
+    error: &'a E,
+}
+
+/// The formatted help text of an error on its own, with colors but without
+/// a trailing newline. Displays as nothing if there is no help text. Created
+/// with [`UFE::helptext_as_display()`](crate::UFE::helptext_as_display).
+#[derive(Debug)]
+pub struct HelptextDisplay<'a, E: UFE + ?Sized> {
+    error: &'a E,
+}
+
+/// The summary of an error followed by every error in its source chain,
+/// without any formatting. On a single line by default, as in
+/// `Failed to load config: caused by: File not found`, or one error per line
+/// with [`multiline()`](ChainDisplay::multiline). Created with
+/// [`UFE::chain_display()`](crate::UFE::chain_display).
+#[derive(Debug)]
+pub struct ChainDisplay<'a, E: UFE + ?Sized> {
+    error: &'a E,
+    multiline: bool,
+}
+
+impl<'a, E: UFE + ?Sized> ChainDisplay<'a, E> {
+    pub(crate) fn new(error: &'a E) -> ChainDisplay<'a, E> {
+        ChainDisplay {
+            error,
+            multiline: false,
+        }
+    }
+
+    /// Puts each error in the chain on its own line, indented below the
+    /// summary.
+    pub fn multiline(mut self) -> ChainDisplay<'a, E> {
+        self.multiline = true;
+        self
+    }
+
+    /// Puts the whole chain on a single line (the default).
+    pub fn inline(mut self) -> ChainDisplay<'a, E> {
+        self.multiline = false;
+        self
+    }
+}
+
+impl<'a, E: UFE + ?Sized> SummaryDisplay<'a, E> {
+    pub(crate) fn new(error: &'a E) -> SummaryDisplay<'a, E> {
+        SummaryDisplay { error }
+    }
+}
+
+impl<'a, E: UFE + ?Sized> ReasonsDisplay<'a, E> {
+    pub(crate) fn new(error: &'a E) -> ReasonsDisplay<'a, E> {
+        ReasonsDisplay { error }
+    }
+}
+
+impl<'a, E: UFE + ?Sized> HelptextDisplay<'a, E> {
+    pub(crate) fn new(error: &'a E) -> HelptextDisplay<'a, E> {
+        HelptextDisplay { error }
+    }
+}
+
+impl<E: UFE + ?Sized> Display for SummaryDisplay<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let code = self.error.code();
+        f.write_str(&pretty_summary(&self.error.summary(), code.as_deref()))
+    }
+}
+
+impl<E: UFE + ?Sized> Display for ReasonsDisplay<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&pretty_reasons(self.error.reasons()).unwrap_or_default())
+    }
+}
+
+impl<E: UFE + ?Sized> Display for HelptextDisplay<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&pretty_helptext(self.error.helptext(), &[]).unwrap_or_default())
+    }
+}
+
+impl<E: UFE + ?Sized> Display for ChainDisplay<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let separator = if self.multiline {
+            "\n  caused by: "
+        } else {
+            ": caused by: "
+        };
+        f.write_str(&redact(&self.error.summary()))?;
+        for source in error_sources(self.error.source()).unwrap_or_default() {
+            f.write_str(separator)?;
+            f.write_str(&redact(&source))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::terminal::strip_ansi;
+    use crate::{UserFacingError, UFE};
+    use std::error::Error;
+    use std::fmt;
+
+    #[test]
+    fn sections_test() {
+        let e = UserFacingError::new("Test Error")
+            .reason("Reason 1")
+            .help("Try Again");
+        let sections = format!(
+            "{}|{}|{}",
+            e.summary_as_display(),
+            e.reasons_as_display(),
+            e.helptext_as_display()
+        );
+        assert_eq!(
+            strip_ansi(&sections),
+            "Error: Test Error| - Reason 1|Try Again"
+        );
+
+        let e = UserFacingError::new("Test Error");
+        assert_eq!(e.reasons_as_display().to_string(), "");
+        assert_eq!(e.helptext_as_display().to_string(), "");
+    }
+
+    #[derive(Debug)]
+    struct CacheError(std::io::Error);
+
+    impl fmt::Display for CacheError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "Failed to write cache")
+        }
+    }
+
+    impl Error for CacheError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn chain_test() {
+        let source = CacheError(std::io::Error::other("Disk full"));
+        let e = UserFacingError::new("Test Error").with_source(source);
+        assert_eq!(
+            e.chain_display().to_string(),
+            "Test Error: caused by: Failed to write cache: caused by: Disk full"
+        );
+        assert_eq!(
+            e.chain_display().multiline().to_string(),
+            "Test Error\n  caused by: Failed to write cache\n  caused by: Disk full"
+        );
+        assert_eq!(
+            e.chain_display().multiline().inline().to_string(),
+            e.chain_display().to_string()
+        );
+        assert_eq!(
+            UserFacingError::new("Test Error")
+                .chain_display()
+                .to_string(),
+            "Test Error"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+// Conversions from the semver crate's errors. Enabled by the "semver" feature.
+
+use std::borrow::Cow;
+
+use crate::{messages, Reason, UserFacingError};
+
+/// Allows you to create UserFacingErrors From semver::Errors, so that a user
+/// supplied version (or version requirement) which fails to parse can be
+/// reported nicely. The parse error is the reason.
+/// # Example
+/// ```
+/// use user_error::UserFacingError;
+/// let ufe: UserFacingError = semver::Version::parse("1.x").unwrap_err().into();
+/// ```
+impl From<semver::Error> for UserFacingError {
+    fn from(error: semver::Error) -> UserFacingError {
+        UserFacingError {
+            reasons: Some(vec![Reason::from(error.to_string())]),
+            helptext: Some(Cow::Owned(messages::message("semver-help"))),
+            source: Some(Box::new(error)),
+            ..UserFacingError::new(messages::message("semver-summary"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{UserFacingError, UFE};
+
+    #[test]
+    fn malformed_version_test() {
+        let error = semver::Version::parse("1.2.x").unwrap_err();
+        let ufe = UserFacingError::from(error);
+        assert_eq!(ufe.summary(), "Invalid version");
+        assert_eq!(ufe.reasons().unwrap().len(), 1);
+        let helptext = ufe.helptext().unwrap();
+        assert!(helptext.contains("1.2.3") && helptext.contains("^1.0"));
+        assert!(ufe.source.is_some());
+    }
+}
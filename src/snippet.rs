@@ -0,0 +1,165 @@
+// Showing the line of a source file an error is about, rustc style, with the
+// offending span underlined. Values the user typed in can be shown the same
+// way, without the file and line number.
+
+use crate::theme::Theme;
+use crate::wrap::display_width;
+use std::ops::Range;
+use std::path::PathBuf;
+
+// Indentation of a highlighted value, so that it lines up with the reasons
+const SPAN_INDENT: &str = "   ";
+
+/// A value (e.g. a command line argument), and the byte range within it that
+/// is underlined.
+#[derive(Debug, Clone)]
+pub(crate) struct Span {
+    pub(crate) text: String,
+    pub(crate) range: Range<usize>,
+}
+
+impl Span {
+    /// Creates a span, shrinking the range to fit the text and widening it
+    /// to the characters it falls in the middle of.
+    pub(crate) fn new(text: &str, range: Range<usize>) -> Span {
+        let mut start = range.start.min(text.len());
+        while !text.is_char_boundary(start) {
+            start -= 1;
+        }
+        let mut end = range.end.clamp(start, text.len());
+        while !text.is_char_boundary(end) {
+            end += 1;
+        }
+        Span {
+            text: text.to_string(),
+            range: start..end,
+        }
+    }
+
+    /// Renders the value on its own line, with the range underlined below.
+    pub(crate) fn render(&self, theme: &Theme) -> String {
+        let before = display_width(&self.text[..self.range.start]);
+        let span = display_width(&self.text[self.range.clone()]);
+        let carets = "^".repeat(span.max(1));
+        [
+            SPAN_INDENT,
+            &theme.reason,
+            &self.text,
+            &theme.reset,
+            "\n",
+            SPAN_INDENT,
+            &" ".repeat(before),
+            &theme.highlight,
+            &carets,
+            &theme.reset,
+        ]
+        .concat()
+    }
+}
+
+/// A line of a source file, and the span within it that is underlined.
+#[derive(Debug, Clone)]
+pub(crate) struct Snippet {
+    pub(crate) path: PathBuf,
+    pub(crate) line: u32,
+    /* Counted in characters, starting from 1 */
+    pub(crate) column: u32,
+    pub(crate) source_line: String,
+    pub(crate) span_len: usize,
+}
+
+/// Renders the snippets one after another. The gutter is as wide as the
+/// largest line number, so that all the snippets line up.
+pub(crate) fn render(snippets: &[Snippet], theme: &Theme) -> Option<String> {
+    let width = snippets
+        .iter()
+        .map(|snippet| snippet.line.to_string().len())
+        .max()?;
+    let blank = " ".repeat(width);
+    let gutter = |number: &str| [&*theme.helptext, number, " |", &*theme.reset].concat();
+
+    let mut lines = Vec::with_capacity(snippets.len() * 4);
+    for snippet in snippets {
+        let location = format!(
+            "{}:{}:{}",
+            snippet.path.display(),
+            snippet.line,
+            snippet.column
+        );
+        lines.push([&*theme.helptext, &blank, "--> ", &theme.reset, &location].concat());
+        lines.push(gutter(&blank));
+        let number = format!("{:>width$}", snippet.line, width = width);
+        lines.push([&*gutter(&number), " ", &snippet.source_line].concat());
+
+        /* Line the carets up with the span, however wide the text before it is */
+        let before: String = snippet
+            .source_line
+            .chars()
+            .take(snippet.column.saturating_sub(1) as usize)
+            .collect();
+        let carets = "^".repeat(snippet.span_len.max(1));
+        lines.push(
+            [
+                &*gutter(&blank),
+                " ",
+                &" ".repeat(display_width(&before)),
+                &theme.summary,
+                &carets,
+                &theme.reset,
+            ]
+            .concat(),
+        );
+    }
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(line: u32) -> Snippet {
+        Snippet {
+            path: PathBuf::from("config.toml"),
+            line,
+            column: 8,
+            source_line: "port = \"abc\"".to_string(),
+            span_len: 5,
+        }
+    }
+
+    #[test]
+    fn gutter_width_test() {
+        let plain = Theme::plain();
+        let one = " --> config.toml:7:8\n  |\n7 | port = \"abc\"\n  |        ^^^^^";
+        assert_eq!(render(&[snippet(7)], &plain).unwrap(), one);
+        let two = "  --> config.toml:42:8\n   |\n42 | port = \"abc\"\n   |        ^^^^^";
+        assert_eq!(render(&[snippet(42)], &plain).unwrap(), two);
+        let four = "    --> config.toml:1024:8\n     |\n1024 | port = \"abc\"\n     |        ^^^^^";
+        assert_eq!(render(&[snippet(1024)], &plain).unwrap(), four);
+    }
+
+    #[test]
+    fn span_test() {
+        let plain = Theme::plain();
+        let span = Span::new("2024-13-01", 5..7);
+        assert_eq!(span.render(&plain), "   2024-13-01\n        ^^");
+
+        /* The range starts and ends in the middle of 'í' (bytes 1..3) */
+        let span = Span::new("d\u{ed}a 13", 2..2);
+        assert_eq!(span.range, 1..3);
+        assert_eq!(span.render(&plain), "   d\u{ed}a 13\n    ^");
+
+        let span = Span::new("d\u{ed}a", 9..12);
+        assert_eq!(span.range, 4..4);
+    }
+
+    #[test]
+    fn stacked_test() {
+        let rendered = render(&[snippet(7), snippet(1024)], &Theme::plain()).unwrap();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[2], "   7 | port = \"abc\"");
+        assert_eq!(lines[6], "1024 | port = \"abc\"");
+        assert!(render(&[], &Theme::plain()).is_none());
+    }
+}
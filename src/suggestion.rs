@@ -0,0 +1,86 @@
+// Suggested fixes, shown as the line before and after the fix like a
+// one-hunk diff.
+
+use crate::theme::Theme;
+
+// Indentation of a suggestion, so that it lines up with the reasons
+const INDENT: &str = "   ";
+
+/// A description of a fix, and the line before and after it is applied.
+#[derive(Debug, Clone)]
+pub(crate) struct Suggestion {
+    pub(crate) description: String,
+    pub(crate) before: String,
+    pub(crate) after: String,
+}
+
+impl Suggestion {
+    /// Renders the description, then the line before the fix marked with a
+    /// '-', then the line after marked with a '+'.
+    fn render(&self, theme: &Theme) -> String {
+        [
+            INDENT,
+            &theme.reason,
+            &self.description,
+            &theme.reset,
+            "\n",
+            INDENT,
+            &theme.removed,
+            "- ",
+            &self.before,
+            &theme.reset,
+            "\n",
+            INDENT,
+            &theme.added,
+            "+ ",
+            &self.after,
+            &theme.reset,
+        ]
+        .concat()
+    }
+}
+
+/// Renders the suggestions one after another.
+pub(crate) fn render(suggestions: &[Suggestion], theme: &Theme) -> Option<String> {
+    if suggestions.is_empty() {
+        return None;
+    }
+    let rendered: Vec<String> = suggestions
+        .iter()
+        .map(|suggestion| suggestion.render(theme))
+        .collect();
+    Some(rendered.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestion() -> Suggestion {
+        Suggestion {
+            description: "Quote the port".to_string(),
+            before: "port = abc".to_string(),
+            after: "port = \"abc\"".to_string(),
+        }
+    }
+
+    #[test]
+    fn diff_test() {
+        let dark = Theme::dark();
+        let expected = format!(
+            "   {}Quote the port{}\n   {}- port = abc{}\n   {}+ port = \"abc\"{}",
+            dark.reason, dark.reset, dark.removed, dark.reset, dark.added, dark.reset
+        );
+        assert_eq!(render(&[suggestion()], &dark).unwrap(), expected);
+    }
+
+    #[test]
+    fn plain_test() {
+        let expected = "   Quote the port\n   - port = abc\n   + port = \"abc\"";
+        assert_eq!(render(&[suggestion()], &Theme::plain()).unwrap(), expected);
+
+        let stacked = render(&[suggestion(), suggestion()], &Theme::plain()).unwrap();
+        assert_eq!(stacked, [expected, expected].join("\n"));
+        assert!(render(&[], &Theme::plain()).is_none());
+    }
+}
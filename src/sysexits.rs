@@ -0,0 +1,54 @@
+// The conventional exit codes for programs that fail, from BSD's sysexits.h.
+
+/// The exit codes defined by BSD's `sysexits.h`, which many Unix programs use
+/// to say why they failed. Set the one an error exits with using
+/// [`UserFacingError::with_sysexits_code()`](crate::UserFacingError::with_sysexits_code).
+/// # Example
+/// ```
+/// use user_error::SysexitCode;
+/// assert_eq!(i32::from(SysexitCode::Usage), 64);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SysexitCode {
+    /// Successful termination (EX_OK, 0)
+    Ok = 0,
+    /// The command was used incorrectly, e.g. with the wrong arguments
+    /// (EX_USAGE, 64)
+    Usage = 64,
+    /// The input data was incorrect in some way (EX_DATAERR, 65)
+    DataErr = 65,
+    /// An input file did not exist or was not readable (EX_NOINPUT, 66)
+    NoInput = 66,
+    /// The user specified did not exist (EX_NOUSER, 67)
+    NoUser = 67,
+    /// The host specified did not exist (EX_NOHOST, 68)
+    NoHost = 68,
+    /// A service is unavailable (EX_UNAVAILABLE, 69)
+    Unavailable = 69,
+    /// An internal software error was detected (EX_SOFTWARE, 70)
+    Software = 70,
+    /// An operating system error was detected, e.g. fork failed (EX_OSERR,
+    /// 71)
+    OsErr = 71,
+    /// A system file did not exist or was not readable (EX_OSFILE, 72)
+    OsFile = 72,
+    /// An output file could not be created (EX_CANTCREAT, 73)
+    CantCreat = 73,
+    /// An error occurred while doing IO on a file (EX_IOERR, 74)
+    IoErr = 74,
+    /// A temporary failure; the user is invited to retry (EX_TEMPFAIL, 75)
+    TempFail = 75,
+    /// The remote system returned something invalid during a protocol
+    /// exchange (EX_PROTOCOL, 76)
+    Protocol = 76,
+    /// Insufficient permission to perform the operation (EX_NOPERM, 77)
+    NoPerm = 77,
+    /// Something was found unconfigured or misconfigured (EX_CONFIG, 78)
+    Config = 78,
+}
+
+impl From<SysexitCode> for i32 {
+    fn from(code: SysexitCode) -> i32 {
+        code as i32
+    }
+}
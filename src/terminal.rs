@@ -0,0 +1,259 @@
+// What the terminal the errors are printed to is capable of displaying.
+
+use crate::config;
+use std::sync::OnceLock;
+
+// Whether ASCII-only rendering was detected from the environment (computed at
+// most once)
+static DETECTED: OnceLock<bool> = OnceLock::new();
+
+/// Forces rendering to use only 7-bit ASCII (true), or allows Unicode and
+/// ANSI escape codes (false), overriding the detection from the environment.
+/// In ASCII-only mode errors are printed without any color, and any
+/// non-ASCII character is replaced with an ASCII look-alike, or '?'.
+/// # Example
+/// ```
+/// user_error::set_ascii_only(true);
+/// ```
+pub fn set_ascii_only(ascii_only: bool) {
+    config::update(|config| config.ascii_only = Some(ascii_only));
+}
+
+/// Returns true if errors are rendered using only 7-bit ASCII. Unless set
+/// with [`set_ascii_only()`], this is the case when TERM is "dumb", or the
+/// locale (LC_ALL, LC_CTYPE or LANG) is set to something other than UTF-8.
+pub fn ascii_only() -> bool {
+    config::read(|config| config.ascii_only).unwrap_or_else(|| {
+        *DETECTED.get_or_init(|| {
+            let var = |key: &str| std::env::var(key).ok();
+            detect_ascii_only(var("TERM").as_deref(), locale(var).as_deref())
+        })
+    })
+}
+
+// The locale in effect: the first of LC_ALL, LC_CTYPE and LANG that is set
+fn locale(var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|key| var(key))
+        .find(|value| !value.is_empty())
+}
+
+// ASCII-only if the terminal is dumb, or the locale is set but isn't UTF-8.
+// An unset locale (e.g. on Windows) is assumed to be capable of Unicode.
+fn detect_ascii_only(term: Option<&str>, locale: Option<&str>) -> bool {
+    if term == Some("dumb") {
+        return true;
+    }
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_ascii_lowercase();
+            !(locale.contains("utf-8") || locale.contains("utf8"))
+        }
+        None => false,
+    }
+}
+
+/// Sets the width of the terminal in columns, which errors are wrapped to,
+/// overriding the COLUMNS environment variable. Set it to 0 to go back to
+/// using COLUMNS.
+/// # Example
+/// ```
+/// user_error::set_width(80);
+/// ```
+pub fn set_width(columns: usize) {
+    config::update(|config| config.width = Some(columns).filter(|&columns| columns > 0));
+}
+
+/// Sets the maximum width errors are wrapped to, however wide the terminal
+/// is. Long lines are hard to read on very wide terminals. There is no
+/// maximum by default.
+/// # Example
+/// ```
+/// user_error::set_max_width(100);
+/// ```
+pub fn set_max_width(columns: usize) {
+    config::update(|config| config.max_width = Some(columns));
+}
+
+/// Returns the width of the terminal in columns: the one set with
+/// [`set_width()`], or else the COLUMNS environment variable (if set).
+pub fn terminal_width() -> Option<usize> {
+    config::read(|config| config.width)
+        .or_else(|| columns(std::env::var("COLUMNS").ok().as_deref()))
+}
+
+// Parses the value of the COLUMNS environment variable
+fn columns(value: Option<&str>) -> Option<usize> {
+    value
+        .and_then(|value| value.trim().parse().ok())
+        .filter(|&columns| columns > 0)
+}
+
+// The width errors are wrapped to: the terminal width, capped at the maximum
+// width, less the room taken up by a box around the error (if any). Errors
+// are not wrapped if neither is known.
+pub(crate) fn wrap_width() -> Option<usize> {
+    effective_width(terminal_width(), config::read(|config| config.max_width))
+        .map(|width| width.saturating_sub(crate::frame::reserved()))
+}
+
+fn effective_width(terminal: Option<usize>, max: Option<usize>) -> Option<usize> {
+    match (terminal, max) {
+        (Some(width), Some(max)) => Some(width.min(max)),
+        (width, max) => width.or(max),
+    }
+}
+
+/// Replaces every non-ASCII character with an ASCII look-alike, or '?' if
+/// there isn't one.
+pub(crate) fn to_ascii(text: &str) -> String {
+    let mut ascii = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            c if c.is_ascii() => ascii.push(c),
+            '\u{2026}' => ascii.push_str("..."),
+            '\u{251c}' => ascii.push('|'),
+            '\u{2514}' => ascii.push('`'),
+            '\u{2500}' | '\u{2022}' | '\u{2013}' | '\u{2014}' => ascii.push('-'),
+            '\u{2502}' => ascii.push('|'),
+            '\u{2018}' | '\u{2019}' => ascii.push('\''),
+            '\u{201c}' | '\u{201d}' => ascii.push('"'),
+            _ => ascii.push('?'),
+        }
+    }
+    ascii
+}
+
+/// Removes all ANSI escape sequences (e.g. colors) from the text.
+pub(crate) fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        match escape_len(rest) {
+            Some(len) => rest = &rest[len..],
+            None => {
+                stripped.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    stripped
+}
+
+/// The length in bytes of the ANSI escape sequence the text starts with, if
+/// it starts with one.
+pub(crate) fn escape_len(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    if chars.next()?.1 != '\u{001b}' {
+        return None;
+    }
+    let end = |next: Option<(usize, char)>| next.map_or(text.len(), |(i, c)| i + c.len_utf8());
+    match chars.next() {
+        /* Control Sequence: ESC [ <parameters> <final byte in @..=~> */
+        Some((_, '[')) => Some(end(chars.find(|(_, c)| ('@'..='~').contains(c)))),
+        /* Operating System Command (e.g. a hyperlink): ESC ] <text>
+         * ended by BEL or ESC \ */
+        Some((_, ']')) => {
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '\u{0007}' => return Some(i + 1),
+                    '\u{001b}' => return Some(end(chars.next())),
+                    _ => {}
+                }
+            }
+            Some(text.len())
+        }
+        _ => Some(1),
+    }
+}
+
+/// Makes the text a link to the URL for terminals that support OSC 8
+/// hyperlinks. Others show the text as it is.
+pub(crate) fn hyperlink(url: &str, text: &str) -> String {
+    [
+        "\u{001b}]8;;",
+        url,
+        "\u{001b}\\",
+        text,
+        "\u{001b}]8;;\u{001b}\\",
+    ]
+    .concat()
+}
+
+/// Makes rendered text safe for the terminal: unchanged, unless rendering is
+/// ASCII-only.
+pub(crate) fn ascii_safe(text: String) -> String {
+    if ascii_only() {
+        to_ascii(&text)
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_ascii_only_test() {
+        assert!(detect_ascii_only(Some("dumb"), Some("en_US.UTF-8")));
+        assert!(detect_ascii_only(Some("xterm"), Some("C")));
+        assert!(detect_ascii_only(None, Some("en_US.ISO-8859-1")));
+        assert!(!detect_ascii_only(Some("xterm"), Some("en_US.utf8")));
+        assert!(!detect_ascii_only(Some("xterm"), None));
+    }
+
+    #[test]
+    fn locale_precedence_test() {
+        let var = |key: &str| match key {
+            "LC_ALL" => Some(String::new()),
+            "LC_CTYPE" => Some("C".to_string()),
+            _ => Some("en_US.UTF-8".to_string()),
+        };
+        assert_eq!(locale(var), Some("C".to_string()));
+    }
+
+    #[test]
+    fn width_test() {
+        assert_eq!(columns(Some("120")), Some(120));
+        assert_eq!(columns(Some("0")), None);
+        assert_eq!(columns(Some("wide")), None);
+        assert_eq!(columns(None), None);
+
+        assert_eq!(effective_width(Some(240), Some(100)), Some(100));
+        assert_eq!(effective_width(Some(80), Some(100)), Some(80));
+        assert_eq!(effective_width(Some(80), None), Some(80));
+        assert_eq!(effective_width(None, Some(100)), Some(100));
+        assert_eq!(effective_width(None, None), None);
+    }
+
+    #[test]
+    fn strip_ansi_test() {
+        let styled = "\u{001b}[97;41;22mError:\u{001b}[91;49;1m Failed\u{001b}[0m";
+        assert_eq!(strip_ansi(styled), "Error: Failed");
+        assert_eq!(strip_ansi("No styling"), "No styling");
+        let linked = "\u{001b}]8;;https://example.com\u{0007}Report\u{001b}]8;;\u{001b}\\ it";
+        assert_eq!(strip_ansi(linked), "Report it");
+        assert_eq!(
+            strip_ansi(&hyperlink("https://example.com", "Report it")),
+            "Report it"
+        );
+        assert_eq!(strip_ansi("\u{001b}x\u{001b}"), "x");
+    }
+
+    #[test]
+    fn escape_len_test() {
+        assert_eq!(escape_len("\u{001b}[1mbold"), Some(4));
+        assert_eq!(escape_len("\u{001b}]8;;a\u{001b}\\link"), Some(8));
+        assert_eq!(escape_len("\u{001b}]8;;a\u{0007}link"), Some(7));
+        assert_eq!(escape_len("\u{001b}[1"), Some(3));
+        assert_eq!(escape_len("plain"), None);
+    }
+
+    #[test]
+    fn to_ascii_test() {
+        assert_eq!(to_ascii("\u{2026} and 3 more"), "... and 3 more");
+        assert_eq!(to_ascii("\u{251c}\u{2500} caf\u{e9}"), "|- caf?");
+    }
+}
@@ -0,0 +1,72 @@
+// Helpers for testing how errors are rendered. Enabled by the "testing"
+// feature.
+
+use crate::terminal::strip_ansi;
+
+/// Asserts that two rendered errors show the same text, ignoring any colors
+/// or other ANSI escape codes. On a mismatch it panics with each line that
+/// differs, so that the difference is readable.
+/// # Example
+/// ```
+/// use user_error::{assert_renders_eq, UserFacingError};
+/// let err = UserFacingError::new("File failed to open").reason("File not found");
+/// assert_renders_eq(&err.to_string(), "Error: File failed to open\n - File not found\n");
+/// ```
+#[track_caller]
+pub fn assert_renders_eq(actual: &str, expected: &str) {
+    let (actual, expected) = (strip_ansi(actual), strip_ansi(expected));
+    if actual != expected {
+        panic!(
+            "rendered text differs (ignoring colors):\n{}",
+            diff(&actual, &expected)
+        );
+    }
+}
+
+// Lists each line that differs between the two texts, by line number
+fn diff(actual: &str, expected: &str) -> String {
+    let actual: Vec<&str> = actual.split('\n').collect();
+    let expected: Vec<&str> = expected.split('\n').collect();
+    let show =
+        |line: Option<&&str>| line.map_or("<none>".to_string(), |line| format!("{:?}", line));
+
+    let mut lines = Vec::new();
+    for i in 0..actual.len().max(expected.len()) {
+        let (a, e) = (actual.get(i), expected.get(i));
+        if a != e {
+            lines.push(format!(
+                "line {}:\n  expected: {}\n    actual: {}",
+                i + 1,
+                show(e),
+                show(a)
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_eq_test() {
+        let styled = "\u{001b}[97;41;22mError:\u{001b}[91;49;1m Failed\u{001b}[0m\n";
+        assert_renders_eq(styled, "Error: Failed\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "line 2:\n  expected: \" - Two\"\n    actual: \" - One\"")]
+    fn renders_ne_test() {
+        assert_renders_eq("Error: Failed\n - One\n", "Error: Failed\n - Two\n");
+    }
+
+    #[test]
+    fn diff_test() {
+        assert_eq!(
+            diff("a\nb", "a"),
+            "line 2:\n  expected: <none>\n    actual: \"b\""
+        );
+        assert_eq!(diff("a", "a"), "");
+    }
+}
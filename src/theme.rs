@@ -0,0 +1,214 @@
+// Color palettes used when pretty printing errors, and the selection of which
+// one is in use.
+
+use crate::config;
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// The ANSI escape sequences used to style each part of an error. Two presets
+/// are provided: [`Theme::dark()`] for dark terminal backgrounds (the default)
+/// and [`Theme::light()`] for light ones.
+/// # Example
+/// ```
+/// use user_error::{set_theme, Theme};
+/// set_theme(Theme::light());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// Style of the 'Error:' label in front of the summary
+    pub label: Cow<'static, str>,
+    /// Style of the summary text
+    pub summary: Cow<'static, str>,
+    /// Style of the ' - ' bullet point in front of each reason
+    pub bullet: Cow<'static, str>,
+    /// Style of the reason text
+    pub reason: Cow<'static, str>,
+    /// Style of the help text
+    pub helptext: Cow<'static, str>,
+    /// Style of keywords highlighted within the reasons and help text
+    pub highlight: Cow<'static, str>,
+    /// Style of a command suggested in the help text, set apart from the
+    /// text around it so that it can be spotted and copied
+    pub command: Cow<'static, str>,
+    /// Style of the line a suggested fix replaces, and its '-' marker
+    pub removed: Cow<'static, str>,
+    /// Style of the line a suggested fix replaces it with, and its '+' marker
+    pub added: Cow<'static, str>,
+    /// Style of the footer printed after everything else
+    pub footer: Cow<'static, str>,
+    /// Ends the styling of each part
+    pub reset: Cow<'static, str>,
+    /// Whether icons are printed in front of the label and the help text
+    pub icons: bool,
+    /// Icon in front of the 'Error:' label
+    pub error_icon: Cow<'static, str>,
+    /// Icon in front of the help text
+    pub help_icon: Cow<'static, str>,
+}
+
+impl Theme {
+    /// Palette for dark terminal backgrounds: a bold red summary, yellow
+    /// bullet points, bold white reasons, muted white help text, bold cyan
+    /// highlights, commands in black on white, bold green fixes and a dim gray
+    /// footer.
+    pub const fn dark() -> Theme {
+        Theme {
+            label: Cow::Borrowed("\u{001b}[97;41;22m"),
+            summary: Cow::Borrowed("\u{001b}[91;49;1m"),
+            bullet: Cow::Borrowed("\u{001b}[93;49;1m"),
+            reason: Cow::Borrowed("\u{001b}[97;49;1m"),
+            helptext: Cow::Borrowed("\u{001b}[37;49;2m"),
+            highlight: Cow::Borrowed("\u{001b}[96;49;1m"),
+            command: Cow::Borrowed("\u{001b}[30;47;22m"),
+            removed: Cow::Borrowed("\u{001b}[37;49;2m"),
+            added: Cow::Borrowed("\u{001b}[92;49;1m"),
+            footer: Cow::Borrowed("\u{001b}[90;49;2m"),
+            reset: Cow::Borrowed("\u{001b}[0m"),
+            icons: false,
+            error_icon: Cow::Borrowed("\u{274c}"),
+            help_icon: Cow::Borrowed("\u{1f4a1}"),
+        }
+    }
+
+    /// Palette for light terminal backgrounds: a bold dark red summary, dark
+    /// yellow bullet points, bold black reasons, dim dark gray help text, bold
+    /// blue highlights, commands in white on black, bold green fixes and a dim
+    /// light gray footer.
+    pub const fn light() -> Theme {
+        Theme {
+            label: Cow::Borrowed("\u{001b}[97;41;22m"),
+            summary: Cow::Borrowed("\u{001b}[31;49;1m"),
+            bullet: Cow::Borrowed("\u{001b}[33;49;1m"),
+            reason: Cow::Borrowed("\u{001b}[30;49;1m"),
+            helptext: Cow::Borrowed("\u{001b}[90;49;2m"),
+            highlight: Cow::Borrowed("\u{001b}[34;49;1m"),
+            command: Cow::Borrowed("\u{001b}[97;40;22m"),
+            removed: Cow::Borrowed("\u{001b}[90;49;2m"),
+            added: Cow::Borrowed("\u{001b}[32;49;1m"),
+            footer: Cow::Borrowed("\u{001b}[37;49;2m"),
+            reset: Cow::Borrowed("\u{001b}[0m"),
+            icons: false,
+            error_icon: Cow::Borrowed("\u{274c}"),
+            help_icon: Cow::Borrowed("\u{1f4a1}"),
+        }
+    }
+
+    /// No styling at all. Used when rendering is ASCII-only.
+    pub const fn plain() -> Theme {
+        Theme {
+            label: Cow::Borrowed(""),
+            summary: Cow::Borrowed(""),
+            bullet: Cow::Borrowed(""),
+            reason: Cow::Borrowed(""),
+            helptext: Cow::Borrowed(""),
+            highlight: Cow::Borrowed(""),
+            command: Cow::Borrowed(""),
+            removed: Cow::Borrowed(""),
+            added: Cow::Borrowed(""),
+            footer: Cow::Borrowed(""),
+            reset: Cow::Borrowed(""),
+            icons: false,
+            error_icon: Cow::Borrowed("\u{274c}"),
+            help_icon: Cow::Borrowed("\u{1f4a1}"),
+        }
+    }
+
+    /// Turns the icons in front of the label and the help text on or off.
+    /// They are off by default, and never printed when rendering is
+    /// ASCII-only.
+    /// # Example
+    /// ```
+    /// use user_error::{set_theme, Theme};
+    /// set_theme(Theme::dark().icons(true));
+    /// ```
+    pub fn icons(mut self, icons: bool) -> Theme {
+        self.icons = icons;
+        self
+    }
+
+    // The icon followed by a space, if icons are turned on
+    pub(crate) fn icon_prefix(&self, icon: &str) -> String {
+        if self.icons {
+            [icon, " "].concat()
+        } else {
+            String::new()
+        }
+    }
+
+    /// Picks the palette that suits the terminal background, as reported by
+    /// the COLORFGBG environment variable. Falls back to the dark palette if
+    /// the variable is not set or cannot be understood.
+    pub fn detect() -> Theme {
+        Theme::from_colorfgbg(std::env::var("COLORFGBG").ok().as_deref())
+    }
+
+    /// Picks the palette that suits a COLORFGBG value such as "15;0" (white
+    /// on black) or "0;15" (black on white). The background is the last
+    /// field. Falls back to the dark palette if the value cannot be
+    /// understood.
+    pub fn from_colorfgbg(colorfgbg: Option<&str>) -> Theme {
+        let background = colorfgbg
+            .and_then(|value| value.rsplit(';').next())
+            .and_then(|background| background.trim().parse::<u8>().ok());
+
+        match background {
+            /* White, and the bright colors other than dark gray */
+            Some(7) | Some(9..=15) => Theme::light(),
+            _ => Theme::dark(),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::dark()
+    }
+}
+
+// The theme detected from the environment (computed at most once)
+static DETECTED: OnceLock<Theme> = OnceLock::new();
+
+/// Sets the theme used to pretty print all errors, overriding the one detected
+/// from the environment.
+pub fn set_theme(theme: Theme) {
+    config::update(|config| config.theme = Some(theme));
+}
+
+/// Returns the theme used to pretty print errors: the one set with
+/// [`set_theme()`], or else the one detected from the environment.
+pub fn current_theme() -> Theme {
+    config::read(|config| config.theme.clone())
+        .unwrap_or_else(|| DETECTED.get_or_init(Theme::detect).clone())
+}
+
+// The theme to render with right now, which has no styling at all if
+// rendering is ASCII-only.
+pub(crate) fn active_theme() -> Theme {
+    if crate::ascii_only() {
+        Theme::plain()
+    } else {
+        current_theme()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colorfgbg_test() {
+        assert_eq!(Theme::from_colorfgbg(Some("15;0")), Theme::dark());
+        assert_eq!(Theme::from_colorfgbg(Some("0;15")), Theme::light());
+        assert_eq!(Theme::from_colorfgbg(Some("0;default;15")), Theme::light());
+        assert_eq!(Theme::from_colorfgbg(Some("garbage")), Theme::dark());
+        assert_eq!(Theme::from_colorfgbg(None), Theme::dark());
+    }
+
+    #[test]
+    fn light_palette_test() {
+        let light = Theme::light();
+        assert_eq!(light.summary, "\u{001b}[31;49;1m");
+        assert_eq!(light.reason, "\u{001b}[30;49;1m");
+        assert_eq!(light.helptext, "\u{001b}[90;49;2m");
+    }
+}
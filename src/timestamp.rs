@@ -0,0 +1,58 @@
+// Timestamps in front of errors, for log-like output (enabled by the
+// "timestamp" feature) and in crash reports.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Where the time an error is stamped with comes from
+#[cfg(feature = "timestamp")]
+pub(crate) type Clock = fn() -> SystemTime;
+
+/// Formats a time as an RFC 3339 UTC timestamp, to the second, e.g.
+/// "2024-01-01T12:00:00Z". Times before 1970 are clamped to the epoch.
+pub(crate) fn rfc3339(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or(0);
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+
+    /* Days since the epoch to a date, counting in 400 year eras from
+     * 0000-03-01 so that leap days come last (see Howard Hinnant's
+     * civil_from_days) */
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn rfc3339_test() {
+        let at = |seconds| rfc3339(UNIX_EPOCH + Duration::from_secs(seconds));
+        assert_eq!(at(0), "1970-01-01T00:00:00Z");
+        assert_eq!(at(1_704_110_400), "2024-01-01T12:00:00Z");
+        assert_eq!(at(1_709_164_799), "2024-02-28T23:59:59Z");
+        assert_eq!(at(1_709_251_199), "2024-02-29T23:59:59Z");
+        assert_eq!(at(951_782_400), "2000-02-29T00:00:00Z");
+    }
+}
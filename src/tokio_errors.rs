@@ -0,0 +1,54 @@
+// Conversions from Tokio's errors. Enabled by the "tokio" feature.
+
+use crate::{messages, UserFacingError};
+use tokio::task::JoinError;
+
+/// Allows you to create UserFacingErrors From tokio::task::JoinErrors, so
+/// that a background task which was cancelled, or which panicked, can be
+/// reported nicely. The panic message (if any) is the reason.
+/// # Example
+/// ```
+/// use user_error::UserFacingError;
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let task = runtime.spawn(std::future::pending::<()>());
+/// task.abort();
+/// let ufe: UserFacingError = runtime.block_on(task).unwrap_err().into();
+/// ```
+impl From<JoinError> for UserFacingError {
+    fn from(error: JoinError) -> UserFacingError {
+        if error.is_cancelled() {
+            return UserFacingError {
+                source: Some(Box::new(error)),
+                ..UserFacingError::new(messages::message("task-summary-cancelled"))
+            };
+        }
+        /* The panic message can only be had by giving up the JoinError */
+        let message = error.try_into_panic().ok().and_then(|payload| {
+            payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+        });
+        let mut ufe = UserFacingError::new(messages::message("task-summary-panicked"));
+        ufe.extend(message);
+        ufe
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{UserFacingError, UFE};
+
+    #[test]
+    fn cancelled_task_test() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let task = runtime.spawn(std::future::pending::<()>());
+        task.abort();
+        let ufe = UserFacingError::from(runtime.block_on(task).unwrap_err());
+        assert_eq!(ufe.summary(), "Task was cancelled");
+        assert!(ufe.reasons().is_none());
+        assert!(ufe.source.is_some());
+    }
+}
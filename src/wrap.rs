@@ -0,0 +1,171 @@
+// Word wrapping of the text of an error to the width of the terminal.
+
+use crate::terminal::{escape_len, strip_ansi};
+
+/// The number of terminal columns a character takes up: two for wide
+/// characters such as CJK ideographs and emoji, none for combining marks and
+/// other zero width characters, one otherwise.
+pub(crate) fn char_width(c: char) -> usize {
+    match c as u32 {
+        /* Combining marks, zero width spaces and joiners, variation selectors */
+        0x0300..=0x036F | 0x200B..=0x200F | 0x20D0..=0x20FF | 0xFE00..=0xFE0F => 0,
+        /* Hangul Jamo, CJK, Hangul syllables, compatibility forms, full width forms */
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6 => 2,
+        /* Emoji presentation symbols */
+        0x231A..=0x231B
+        | 0x23E9..=0x23EC
+        | 0x23F0
+        | 0x23F3
+        | 0x2614..=0x2615
+        | 0x26A1
+        | 0x26AA..=0x26AB
+        | 0x26BD..=0x26BE
+        | 0x26C4..=0x26C5
+        | 0x26D4
+        | 0x26EA
+        | 0x26F2..=0x26F5
+        | 0x26FA
+        | 0x26FD
+        | 0x2705
+        | 0x270A..=0x270B
+        | 0x2728
+        | 0x274C
+        | 0x274E
+        | 0x2753..=0x2755
+        | 0x2757
+        | 0x2795..=0x2797
+        | 0x27B0
+        | 0x27BF
+        | 0x1F000..=0x1FAFF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// The number of terminal columns the text takes up. A character followed
+/// by the emoji variation selector (U+FE0F) is displayed as a wide emoji, and
+/// escape codes (e.g. colors) take up none.
+pub(crate) fn display_width(text: &str) -> usize {
+    let text = strip_ansi(text);
+    let mut width = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if chars.peek() == Some(&'\u{fe0f}') {
+            width += 2;
+        } else {
+            width += char_width(c);
+        }
+    }
+    width
+}
+
+/// Wraps text into lines that fit in `first` columns for the first line, and
+/// `rest` columns for the following ones. Words are only broken up if they
+/// don't fit on a line of their own. Existing line breaks are kept, and
+/// escape codes are never broken up.
+pub(crate) fn wrap(text: &str, first: usize, rest: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for paragraph in text.split('\n') {
+        /* Keep the indentation of the line (e.g. to line up a caret) */
+        let words = paragraph.trim_start_matches(' ');
+        let mut line = paragraph[..paragraph.len() - words.len()].to_string();
+        let mut line_width = line.len();
+        let mut has_words = false;
+
+        for word in words.split(' ').filter(|word| !word.is_empty()) {
+            let available = |lines: &Vec<String>| if lines.is_empty() { first } else { rest };
+
+            /* Move on to the next line if the word doesn't fit on this one */
+            if has_words && line_width + 1 + display_width(word) > available(&lines) {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            } else if has_words {
+                line.push(' ');
+                line_width += 1;
+            }
+
+            /* Break up words that are too long to fit on any line */
+            let mut rest = word;
+            while let Some(c) = rest.chars().next() {
+                /* Escape codes take up no room */
+                if let Some(len) = escape_len(rest) {
+                    line.push_str(&rest[..len]);
+                    rest = &rest[len..];
+                    continue;
+                }
+                let width = char_width(c);
+                if line_width > 0 && line_width + width > available(&lines) {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+                line.push(c);
+                line_width += width;
+                rest = &rest[c.len_utf8()..];
+            }
+            has_words = true;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Wraps text to the given width, given how many columns are already taken
+/// up in front of it on the first line. The following lines are indented by
+/// the same amount so that the text lines up.
+pub(crate) fn wrap_hanging(text: &str, width: Option<usize>, taken: usize) -> String {
+    match width {
+        Some(width) => {
+            let available = width.saturating_sub(taken);
+            let indent = ["\n", &" ".repeat(taken)].concat();
+            wrap(text, available, available).join(&indent)
+        }
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_width_test() {
+        assert_eq!(display_width("Error:"), 6);
+        assert_eq!(display_width("\u{274c} "), 3);
+        assert_eq!(display_width("\u{26a0}\u{fe0f}"), 2);
+        assert_eq!(display_width("\u{65e5}\u{672c}"), 4);
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("\u{001b}[1mbold\u{001b}[0m"), 4);
+    }
+
+    #[test]
+    fn wrap_test() {
+        assert_eq!(wrap("one two three", 7, 7), vec!["one two", "three"]);
+        assert_eq!(wrap("one two three", 3, 9), vec!["one", "two three"]);
+        assert_eq!(wrap("abcdefgh", 3, 3), vec!["abc", "def", "gh"]);
+        assert_eq!(wrap("one\ntwo", 80, 80), vec!["one", "two"]);
+        assert_eq!(wrap("a(\n ^", 80, 80), vec!["a(", " ^"]);
+        assert_eq!(
+            wrap("\u{001b}[1mone\u{001b}[0m two", 3, 3),
+            vec!["\u{001b}[1mone\u{001b}[0m", "two"]
+        );
+        assert_eq!(wrap("\u{001b}[1mabcd", 2, 2), vec!["\u{001b}[1mab", "cd"]);
+        assert_eq!(
+            wrap("\u{1f4a1} \u{1f4a1}", 3, 3),
+            vec!["\u{1f4a1}", "\u{1f4a1}"]
+        );
+    }
+
+    #[test]
+    fn wrap_hanging_test() {
+        assert_eq!(wrap_hanging("one two", Some(6), 3), "one\n   two");
+        assert_eq!(wrap_hanging("one two", None, 3), "one two");
+    }
+}
@@ -0,0 +1,22 @@
+use user_error::{set_ascii_only, set_width, UserFacingError, UFE};
+
+#[test]
+fn ascii_only_rendering_test() {
+    set_ascii_only(true);
+    set_width(200);
+
+    let ufe = UserFacingError::new("Failed to sync \u{201c}caf\u{e9}\u{201d}")
+        .reason("Server unreachable")
+        .reason_indented("\u{251c}\u{2500} DNS lookup failed\u{2026}", 1)
+        .reason_indented("\u{2514}\u{2500} No route to host", 2)
+        .help("Check your connection \u{2014} then retry");
+
+    let rendered = ufe.to_string();
+    assert!(rendered.bytes().all(|byte| byte < 0x80));
+    assert!(!rendered.contains('\u{001b}'));
+    assert!(rendered.contains("  - |- DNS lookup failed..."));
+    assert!(rendered.contains("    - `- No route to host"));
+    assert!(ufe
+        .summary_only_to_string()
+        .starts_with("Error: Failed to sync \"caf?\""));
+}
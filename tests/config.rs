@@ -0,0 +1,39 @@
+use std::thread;
+use user_error::{configure, set_max_width, set_width, Config, OutputStyle, UserFacingError, UFE};
+
+// Options are global, so they're all tested in the one test
+#[test]
+fn config_test() {
+    configure(
+        Config::new()
+            .width(20)
+            .style(OutputStyle::Plain)
+            .quiet(true)
+            .app_name("mytool"),
+    );
+    let current = Config::current();
+    assert_eq!(current.width, Some(20));
+    assert_eq!(current.style, Some(OutputStyle::Plain));
+    assert!(current.quiet);
+
+    /* Wrapped to 20 columns, without colors, reasons or help text */
+    let ufe = UserFacingError::new("Failed to open the configuration file")
+        .reason("File not found")
+        .help("Try: touch config.toml");
+    let expected = "Error: Failed to\n       open the\n       configuration\n       file\n\
+                    2 additional details\nhidden; run `mytool\n--verbose` to see\nthem\n";
+    assert_eq!(ufe.to_printed_string(), expected);
+
+    /* Options set at the same time from different threads all stick */
+    let width = thread::spawn(|| set_width(30));
+    let max_width = thread::spawn(|| set_max_width(25));
+    width.join().unwrap();
+    max_width.join().unwrap();
+    let current = Config::current();
+    assert_eq!(current.width, Some(30));
+    assert_eq!(current.max_width, Some(25));
+    assert!(current.quiet);
+
+    configure(Config::new());
+    assert_eq!(Config::current(), Config::default());
+}
@@ -0,0 +1,31 @@
+use std::env::consts::{ARCH, OS};
+use user_error::{configure, Config, OutputStyle, UserFacingError, UFE};
+
+// Verbose mode is global, so this is the only test in this file
+#[test]
+fn environment_snapshot_test() {
+    std::env::set_var("USER_ERROR_TEST_EDITOR", "vim");
+    std::env::remove_var("USER_ERROR_TEST_TOKEN");
+    let ufe = UserFacingError::new("Failed to open editor")
+        .help("Try Again")
+        .include_environment(&["USER_ERROR_TEST_EDITOR", "USER_ERROR_TEST_TOKEN"]);
+
+    /* Left out unless printing is verbose */
+    configure(Config::new().width(200).style(OutputStyle::Plain));
+    let expected = "Error: Failed to open editor\nTry Again\n";
+    assert_eq!(ufe.to_printed_string(), expected);
+
+    configure(
+        Config::new()
+            .width(200)
+            .style(OutputStyle::Plain)
+            .verbose(true)
+            .app_version("mytool 1.2.0"),
+    );
+    let expected = format!(
+        "Error: Failed to open editor\nTry Again\nEnvironment:\n  version: mytool 1.2.0\n  \
+         os: {} ({})\n  USER_ERROR_TEST_EDITOR: vim\n  USER_ERROR_TEST_TOKEN: (unset)\n",
+        OS, ARCH
+    );
+    assert_eq!(ufe.to_printed_string(), expected);
+}
@@ -0,0 +1,44 @@
+use user_error::{configure, Config, OutputStyle, UserFacingError, UFE};
+
+// Options are global, so they're all tested in the one test
+#[test]
+fn hidden_details_test() {
+    let base = Config::new()
+        .width(200)
+        .ascii_only(false)
+        .style(OutputStyle::Plain)
+        .app_name("mytool");
+    let ufe = UserFacingError::new("Failed to sync")
+        .reason("Reason 1")
+        .reason("Reason 2")
+        .reason("Reason 3")
+        .help("Try Again");
+
+    /* Nothing hidden, no notice */
+    configure(base.clone());
+    let expected = "Error: Failed to sync\n - Reason 1\n - Reason 2\n - Reason 3\nTry Again\n";
+    assert_eq!(ufe.to_printed_string(), expected);
+
+    /* Hidden by the maximum number of reasons */
+    configure(base.clone().max_reasons(1));
+    let expected = "Error: Failed to sync\n - Reason 1\n   \u{2026} and 2 more\nTry Again\n\
+                    2 additional details hidden; run `mytool --verbose` to see them\n";
+    assert_eq!(ufe.to_printed_string(), expected);
+    let lines: Vec<String> = UserFacingError::new("Failed to sync")
+        .reason("Reason 1")
+        .reason("Reason 2")
+        .into_iter()
+        .collect();
+    assert_eq!(lines[2], "   \u{2026} and 1 more");
+
+    /* Hidden by quiet mode, with a different flag */
+    configure(base.quiet(true).verbose_flag("-v"));
+    let expected = "Error: Failed to sync\n\
+                    4 additional details hidden; run `mytool -v` to see them\n";
+    assert_eq!(ufe.to_printed_string(), expected);
+    let expected = "Error: Failed to sync";
+    assert_eq!(
+        UserFacingError::new("Failed to sync").to_printed_string(),
+        expected
+    );
+}
@@ -0,0 +1,36 @@
+use user_error::{set_ascii_only, set_theme, set_width, Theme, UserFacingError};
+
+// A single test, since the width is global
+#[test]
+fn highlight_wrapped_test() {
+    let theme = Theme::dark();
+    set_theme(theme.clone());
+    set_ascii_only(false);
+    /* "Set --frobnicate-level" fills the first line after the bullet */
+    set_width(25);
+
+    /* A keyword that crosses the line break is still highlighted */
+    let e = UserFacingError::new("Failed")
+        .reason("Set --frobnicate-level to 3")
+        .highlight(&["--frobnicate-level to"]);
+    let expected = [
+        &*theme.highlight,
+        "--frobnicate-level\n   to",
+        &*theme.reason,
+        " 3",
+    ]
+    .concat();
+    assert!(e.to_string().contains(&expected));
+
+    /* As is one broken up for being too long to fit on a line */
+    let path = "/home/user/projects/config/settings.toml";
+    let e = UserFacingError::new("Failed")
+        .help(format!("Check {}", path))
+        .highlight(&[path]);
+    let rendered = e.to_string();
+    let start = rendered.find(&*theme.highlight).unwrap() + theme.highlight.len();
+    let end = start + rendered[start..].find(&*theme.helptext).unwrap();
+    let highlighted: String = rendered[start..end].split_whitespace().collect();
+    assert_eq!(highlighted, path);
+    assert!(rendered[start..end].contains('\n'));
+}
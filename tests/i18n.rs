@@ -0,0 +1,58 @@
+#![cfg(feature = "i18n")]
+use user_error::i18n::{self, MessageProvider};
+use user_error::{UserFacingError, UFE};
+
+// Catalog that only knows a couple of keys, everything else is English
+struct French;
+
+impl MessageProvider for French {
+    fn message(&self, id: &str) -> Option<String> {
+        match id {
+            i18n::ERROR_LABEL => Some("Erreur :".into()),
+            i18n::SUGGEST_COMMAND => Some("Essayez : {command}".into()),
+            "env-missing-reason" => Some("'{var}' n'est pas d\u{e9}finie".into()),
+            _ => None,
+        }
+    }
+
+    fn plural(&self, id: &str, count: usize) -> Option<String> {
+        match (id, count) {
+            (i18n::MORE_REASONS, 0..=1) => Some("\u{2026} et {count} autre".into()),
+            (i18n::MORE_REASONS, _) => Some("\u{2026} et {count} autres".into()),
+            ("network-timeout-reason", _) => {
+                Some("Pas de r\u{e9}ponse de '{host}' en {count} s".into())
+            }
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn translated_catalog_test() {
+    i18n::set_messages(French);
+
+    let rendered = UserFacingError::new("Echec").to_string();
+    assert!(rendered.contains("Erreur :"));
+    assert!(!rendered.contains("Error:"));
+    let e = UserFacingError::new("Echec").with_suggestion_command("cargo clean");
+    assert_eq!(e.helptext().unwrap(), "Essayez : `cargo clean`");
+    /* Ready-made errors are translated too, placeholders and all */
+    let e = UserFacingError::from_env_missing("HOME");
+    assert_eq!(e.reasons().unwrap(), vec!["'HOME' n'est pas d\u{e9}finie"]);
+    assert_eq!(e.helptext().unwrap(), "Set it with: export HOME=<value>");
+    let e = UserFacingError::from_network_timeout("example.com", 30);
+    assert_eq!(
+        e.reasons().unwrap(),
+        vec!["Pas de r\u{e9}ponse de 'example.com' en 30 s"]
+    );
+
+    assert_eq!(i18n::plural(i18n::MORE_REASONS, 1), "\u{2026} et 1 autre");
+    assert_eq!(i18n::plural(i18n::MORE_REASONS, 4), "\u{2026} et 4 autres");
+    /* Unknown keys fall back to English */
+    assert_eq!(i18n::message("not-a-message"), "");
+
+    i18n::clear_messages();
+    let rendered = UserFacingError::new("Failed").to_string();
+    assert!(rendered.contains("Error:"));
+    assert_eq!(i18n::plural(i18n::MORE_REASONS, 4), "\u{2026} and 4 more");
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "i18n")]
+use std::convert::TryFrom;
+use std::io::{Error, ErrorKind};
+use user_error::i18n::{self, MessageProvider};
+use user_error::{UserFacingError, UFE};
+
+// Catalog that translates a few IO messages and the summary of invalid chars
+struct French;
+
+impl MessageProvider for French {
+    fn message(&self, id: &str) -> Option<String> {
+        match id {
+            "io-help-not-found" => Some("V\u{e9}rifiez que le chemin est correct.".into()),
+            "io-reason-read-only-filesystem" => {
+                Some("Le syst\u{e8}me de fichiers est en lecture seule".into())
+            }
+            "invalid-char-summary" => Some("Caract\u{e8}re Unicode invalide".into()),
+            _ => None,
+        }
+    }
+}
+
+// The catalog is global, so this is the only test in this file
+#[test]
+fn translated_conversions_test() {
+    i18n::set_messages(French);
+
+    let e = UserFacingError::from(Error::from(ErrorKind::NotFound));
+    assert_eq!(
+        e.helptext().unwrap(),
+        "V\u{e9}rifiez que le chemin est correct."
+    );
+    let e = UserFacingError::from(Error::from(ErrorKind::ReadOnlyFilesystem));
+    assert_eq!(
+        e.reasons().unwrap(),
+        vec!["Le syst\u{e8}me de fichiers est en lecture seule"]
+    );
+    /* Unknown keys fall back to English */
+    assert_eq!(
+        e.helptext().unwrap(),
+        "Choose a path on a writable file system."
+    );
+
+    /* Conversions from other errors are translated too */
+    let e = UserFacingError::from(char::try_from(0xD800_u32).unwrap_err());
+    assert_eq!(e.summary(), "Caract\u{e8}re Unicode invalide");
+    assert_eq!(e.helptext().unwrap(), "Ensure the input is valid UTF-32");
+
+    i18n::clear_messages();
+    let e = UserFacingError::from(Error::from(ErrorKind::NotFound));
+    assert_eq!(
+        e.helptext().unwrap(),
+        "Check the path is correct and the file exists."
+    );
+}
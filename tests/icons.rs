@@ -0,0 +1,28 @@
+use user_error::{set_ascii_only, set_theme, Theme, UserFacingError};
+
+// A single test, since the theme and ASCII-only mode are global
+#[test]
+fn icons_test() {
+    let ufe = UserFacingError::new("Failed").reason("Reason").help("Help");
+    set_ascii_only(false);
+
+    set_theme(Theme::dark());
+    let rendered = ufe.to_string();
+    assert!(rendered.starts_with("\u{001b}[97;41;22mError:"));
+    assert!(!rendered.contains('\u{1f4a1}'));
+
+    set_theme(Theme::dark().icons(true));
+    let rendered = ufe.to_string();
+    assert!(rendered.starts_with("\u{274c} \u{001b}[97;41;22mError:"));
+    assert!(rendered.contains("\u{1f4a1} \u{001b}[37;49;2mHelp"));
+
+    /* Glyphs can be overridden */
+    let mut theme = Theme::dark().icons(true);
+    theme.help_icon = "?".into();
+    set_theme(theme);
+    assert!(ufe.to_string().contains("? \u{001b}[37;49;2mHelp"));
+
+    /* Never printed when rendering is ASCII-only */
+    set_ascii_only(true);
+    assert_eq!(ufe.to_string(), "Error: Failed\n - Reason\nHelp\n");
+}
@@ -0,0 +1,36 @@
+use user_error::{
+    configure, disable_invocation, set_invocation, Config, OutputStyle, UserFacingError, UFE,
+};
+
+// Capturing the invocation is global, so this is the only test in this file
+#[test]
+fn invocation_test() {
+    let ufe = UserFacingError::new("Failed to sync").help("Try Again");
+    set_invocation(&["mytool", "sync", "--force", "./data"]);
+
+    /* Left out of the rendering unless printing is verbose */
+    configure(Config::new().width(200).style(OutputStyle::Plain));
+    assert_eq!(
+        ufe.to_printed_string(),
+        "Error: Failed to sync\nTry Again\n"
+    );
+
+    configure(
+        Config::new()
+            .width(200)
+            .style(OutputStyle::Plain)
+            .verbose(true),
+    );
+    let expected = "Error: Failed to sync\nTry Again\ninvocation: mytool sync --force ./data\n";
+    assert_eq!(ufe.to_printed_string(), expected);
+    assert!(ufe
+        .to_json()
+        .ends_with(r#","invocation":"mytool sync --force ./data"}"#));
+
+    disable_invocation();
+    assert_eq!(
+        ufe.to_printed_string(),
+        "Error: Failed to sync\nTry Again\n"
+    );
+    assert!(!ufe.to_json().contains("invocation"));
+}
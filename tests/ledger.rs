@@ -0,0 +1,51 @@
+use std::thread;
+use user_error::{
+    configure, enable_error_ledger, set_error_ledger_capacity, take_error_ledger, Config,
+    OutputStyle, UserFacingError, UFE,
+};
+
+// The ledger is global, so this is the only test in this file
+#[test]
+fn error_ledger_test() {
+    configure(Config::new().style(OutputStyle::Plain));
+
+    /* Nothing is recorded until the ledger is enabled */
+    UserFacingError::new("Not recorded").print();
+    assert!(take_error_ledger().is_empty());
+
+    enable_error_ledger();
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            thread::spawn(move || {
+                UserFacingError::new(format!("Failed to process item {}", i))
+                    .with_code(format!("E{}", i))
+                    .print();
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    let mut entries = take_error_ledger();
+    entries.sort_by(|a, b| a.summary.cmp(&b.summary));
+    let summaries: Vec<&str> = entries.iter().map(|e| e.summary.as_str()).collect();
+    assert_eq!(
+        summaries,
+        vec![
+            "Failed to process item 0",
+            "Failed to process item 1",
+            "Failed to process item 2",
+            "Failed to process item 3",
+        ]
+    );
+    assert_eq!(entries[2].code.as_deref(), Some("E2"));
+    assert!(take_error_ledger().is_empty());
+
+    /* Only the most recent errors are kept */
+    set_error_ledger_capacity(2);
+    for i in 0..5 {
+        UserFacingError::new(format!("Error {}", i)).print();
+    }
+    let summaries: Vec<String> = take_error_ledger().into_iter().map(|e| e.summary).collect();
+    assert_eq!(summaries, vec!["Error 3", "Error 4"]);
+}
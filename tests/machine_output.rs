@@ -0,0 +1,77 @@
+use user_error::{
+    configure, disable_machine_output, set_machine_output, Config, MachineOutput, OutputStyle,
+    UserFacingError, UFE,
+};
+
+// The machine output is global, so this is the only test in this file
+#[test]
+fn machine_output_test() {
+    configure(Config::new().style(OutputStyle::Plain));
+    let dir = std::env::temp_dir().join(format!("user-error-machine-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    /* Each printed error is appended as a line of JSON */
+    let path = dir.join("errors.jsonl");
+    set_machine_output(MachineOutput::Path(path.clone()));
+    UserFacingError::new("Failed to sync")
+        .reason("Timed out")
+        .print();
+    UserFacingError::new("Failed to deploy").print();
+    let written = std::fs::read_to_string(&path).unwrap();
+    let summaries: Vec<String> = written
+        .lines()
+        .map(|line| UserFacingError::from_json(line).unwrap().summary())
+        .collect();
+    assert_eq!(summaries, vec!["Failed to sync", "Failed to deploy"]);
+
+    /* A target that can't be written to is skipped */
+    set_machine_output(MachineOutput::Path(
+        dir.join("missing").join("errors.jsonl"),
+    ));
+    UserFacingError::new("Failed to sync").print();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::io::AsRawFd;
+
+        let fd_path = dir.join("fd.jsonl");
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&fd_path)
+            .unwrap();
+        set_machine_output(MachineOutput::Fd(file.as_raw_fd()));
+        UserFacingError::new("Failed to build").print();
+        disable_machine_output();
+        drop(file);
+        let written = std::fs::read_to_string(&fd_path).unwrap();
+        let error = UserFacingError::from_json(written.trim_end()).unwrap();
+        assert_eq!(error.summary(), "Failed to build");
+
+        /* A pipe, such as a file descriptor handed over by a parent process */
+        let mut child = std::process::Command::new("cat")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        set_machine_output(MachineOutput::Fd(stdin.as_raw_fd()));
+        UserFacingError::new("Failed to test").print();
+        disable_machine_output();
+        drop(stdin);
+        let piped = child.wait_with_output().unwrap();
+        let piped = String::from_utf8(piped.stdout).unwrap();
+        let error = UserFacingError::from_json(piped.trim_end()).unwrap();
+        assert_eq!(error.summary(), "Failed to test");
+
+        /* A file descriptor that isn't open is skipped */
+        set_machine_output(MachineOutput::Fd(999));
+        UserFacingError::new("Failed to build").print();
+    }
+
+    disable_machine_output();
+    UserFacingError::new("Not written").print();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), written);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,34 @@
+use std::process::Command;
+use user_error::{configure, install_panic_hook, Config, OutputStyle};
+
+// Panics in a copy of this test run as a separate process, since the panic
+// hook is global and the output goes to stderr
+#[test]
+fn panic_hook_test() {
+    if std::env::var_os("USER_ERROR_PANIC_TEST").is_some() {
+        configure(Config::new().width(200).style(OutputStyle::Plain));
+        install_panic_hook();
+        panic!("Database is locked");
+    }
+
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "panic_hook_test",
+            "--exact",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env("USER_ERROR_PANIC_TEST", "1")
+        .env_remove("RUST_BACKTRACE")
+        .env_remove("RUST_LIB_BACKTRACE")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let expected = "Error: The application crashed unexpectedly\n - Database is locked\n \
+                    - location: tests/panic.rs:";
+    assert!(stderr.contains(expected), "{}", stderr);
+    assert!(stderr.contains("Please report it"));
+    assert!(!stderr.contains("backtrace:"));
+}
@@ -0,0 +1,25 @@
+use user_error::{
+    set_ascii_only, set_output_style, set_theme, set_width, OutputStyle, Theme, UserFacingError,
+    UFE,
+};
+
+// Colors are forced on globally, so this is the only test in this file
+#[test]
+fn render_plain_test() {
+    set_ascii_only(false);
+    set_theme(Theme::dark());
+    set_output_style(OutputStyle::Ansi);
+    set_width(200);
+
+    let ufe = UserFacingError::new("Failed to build project")
+        .reason("Database could not be parsed")
+        .help("Try: cargo clean");
+    assert!(ufe.to_printed_string().contains('\u{001b}'));
+
+    let plain = ufe.render_plain();
+    assert!(!plain.contains('\u{001b}'));
+    assert_eq!(
+        plain,
+        "Error: Failed to build project\n - Database could not be parsed\nTry: cargo clean\n"
+    );
+}
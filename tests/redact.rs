@@ -0,0 +1,31 @@
+use user_error::{
+    configure, redact_values, set_redactor, Config, OutputStyle, UserFacingError, UFE,
+};
+
+fn hide_home(text: &str) -> String {
+    text.replace("/home/amy", "~")
+}
+
+// Redaction is global, so this is the only test in this file
+#[test]
+fn redact_test() {
+    configure(Config::new().width(200).style(OutputStyle::Plain));
+    redact_values(&["password="]);
+    set_redactor(hide_home);
+
+    let ufe = UserFacingError::new("Failed to connect with password=hunter2")
+        .reason("postgres://db?password=hunter2 refused the connection")
+        .help("Check /home/amy/.pgpass");
+    let expected = "Error: Failed to connect with password=[redacted]\n - \
+                    postgres://db?password=[redacted] refused the connection\nCheck ~/.pgpass\n";
+    assert_eq!(ufe.to_printed_string(), expected);
+    assert!(!ufe.to_json().contains("hunter2"));
+
+    /* The error itself is left as it is */
+    assert_eq!(ufe.summary(), "Failed to connect with password=hunter2");
+    assert_eq!(
+        ufe.reasons().unwrap(),
+        vec!["postgres://db?password=hunter2 refused the connection"]
+    );
+    assert_eq!(ufe.helptext().unwrap(), "Check /home/amy/.pgpass");
+}
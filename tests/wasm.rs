@@ -0,0 +1,16 @@
+#![cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+
+use user_error::prelude::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn wasm_print_test() {
+    let err = UserFacingError::new("Failed to build project").reason("main.db not found");
+    assert_eq!(
+        err.render_plain(),
+        "Error: Failed to build project\n - main.db not found\n"
+    );
+    err.print();
+    /* Returns instead of exiting the process */
+    err.print_and_exit();
+}
@@ -0,0 +1,37 @@
+use user_error::{set_max_width, set_width, UserFacingError};
+
+// A single test, since the widths are global
+#[test]
+fn max_width_test() {
+    let word = "word ";
+    let ufe = UserFacingError::new("Failed").reason(word.repeat(40).trim_end());
+
+    /* A simulated 240 column terminal fits the whole reason on one line */
+    set_width(240);
+    let rendered = ufe.to_string();
+    assert_eq!(rendered.lines().count(), 2);
+
+    /* Capping the width at 100 wraps it */
+    set_max_width(100);
+    let rendered = ufe.to_string();
+    let lines: Vec<&str> = rendered.lines().skip(1).collect();
+    assert_eq!(lines.len(), 3);
+    let visible = |line: &str| {
+        line.chars()
+            .filter(|c| *c == ' ' || c.is_alphabetic())
+            .count()
+    };
+    assert!(lines.iter().all(|line| visible(line) <= 100));
+    assert!(visible(lines[0]) > 90);
+    /* Following lines line up with the text after the bullet */
+    assert!(lines[1].starts_with("   word"));
+
+    /* The terminal being narrower than the cap wins */
+    set_width(40);
+    assert!(ufe.to_string().lines().count() > 5);
+
+    /* No cap and no known width: no wrapping */
+    set_max_width(usize::MAX);
+    set_width(240);
+    assert_eq!(ufe.to_string().lines().count(), 2);
+}
@@ -0,0 +1,85 @@
+//! Procedural macros for the user-error crate. Use them through its "derive"
+//! feature rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_macro_input, parse_quote, Expr, ExprClosure, ItemFn, LitStr};
+
+/// Turns every error propagated with `?` in a function into a
+/// UserFacingError with the given summary (and help text, if any).
+/// # Example
+/// ```
+/// use user_error::{user_error, UserFacingError};
+///
+/// #[user_error(summary = "Failed to load config", help = "Check config.toml")]
+/// fn load() -> Result<String, UserFacingError> {
+///     Ok(std::fs::read_to_string("config.toml")?)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn user_error(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut summary: Option<LitStr> = None;
+    let mut help: Option<LitStr> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("summary") {
+            summary = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("help") {
+            help = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `summary` or `help`"))
+        }
+    });
+    parse_macro_input!(args with parser);
+
+    let summary = match summary {
+        Some(summary) => summary,
+        None => {
+            let message = "#[user_error] needs a summary, e.g. #[user_error(summary = \"...\")]";
+            return syn::Error::new(Span::call_site(), message)
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let mut function = parse_macro_input!(item as ItemFn);
+    Enrich { summary, help }.visit_block_mut(&mut function.block);
+    quote!(#function).into()
+}
+
+// Rewrites every `expr?` into `expr.map_err(...)?`, where the error is
+// converted into a UserFacingError with the summary and help text.
+struct Enrich {
+    summary: LitStr,
+    help: Option<LitStr>,
+}
+
+impl VisitMut for Enrich {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        /* Rewrite the inside first, so that nested ? are rewritten too */
+        visit_mut::visit_expr_mut(self, expr);
+
+        if let Expr::Try(try_expr) = expr {
+            let inner = &try_expr.expr;
+            let summary = &self.summary;
+            let help = self.help.iter();
+            let enriched: Expr = parse_quote! {
+                (#inner).map_err(|error| {
+                    let mut ufe = ::user_error::UserFacingError::from(error);
+                    ufe.update(#summary);
+                    ufe #(.help(#help))*
+                })
+            };
+            *try_expr.expr = enriched;
+        }
+    }
+
+    /* A ? in a closure, async block or nested function returns from that
+     * instead, so leave them alone */
+    fn visit_expr_closure_mut(&mut self, _: &mut ExprClosure) {}
+    fn visit_expr_async_mut(&mut self, _: &mut syn::ExprAsync) {}
+    fn visit_item_mut(&mut self, _: &mut syn::Item) {}
+}
@@ -0,0 +1,34 @@
+use std::io;
+use user_error::{user_error, UserFacingError, UFE};
+
+fn read(kind: io::ErrorKind) -> io::Result<String> {
+    Err(io::Error::new(kind, "file.txt"))
+}
+
+#[user_error(summary = "Failed to load config", help = "Check config.toml")]
+fn load() -> Result<String, UserFacingError> {
+    let text = read(io::ErrorKind::InvalidData)?;
+    Ok(text)
+}
+
+#[user_error(summary = "Failed to load config")]
+fn load_without_help() -> Result<usize, UserFacingError> {
+    /* The closure's own ? is left alone */
+    let parse = |text: &str| -> Result<usize, std::num::ParseIntError> {
+        let number = text.parse()?;
+        Ok(number)
+    };
+    assert!(parse("x").is_err());
+    Ok(read(io::ErrorKind::InvalidData)?.len())
+}
+
+#[test]
+fn user_error_test() {
+    let e = load().unwrap_err();
+    assert_eq!(e.summary(), "Failed to load config");
+    assert_eq!(e.helptext().unwrap(), "Check config.toml");
+
+    let e = load_without_help().unwrap_err();
+    assert_eq!(e.summary(), "Failed to load config");
+    assert!(e.helptext().is_none());
+}